@@ -0,0 +1,150 @@
+use super::SECONDS_IN_DAY;
+use universalis_sdk::ItemSaleHistoryUnit;
+
+/// Weekly seasonality of daily sales
+const SEASON_LENGTH: usize = 7;
+const LEVEL_SMOOTHING: f64 = 0.3;
+const TREND_SMOOTHING: f64 = 0.05;
+const SEASONAL_SMOOTHING: f64 = 0.3;
+
+/// Days of sale history the demand forecast is fitted on
+pub const FORECAST_HISTORY_DAYS: u64 = 28;
+pub const FORECAST_HORIZON_DAYS: usize = 7;
+
+/// Units sold per day over the last `days`, the oldest day first
+pub fn get_daily_sales(sales: &[ItemSaleHistoryUnit], now: u64, days: usize) -> Vec<f64> {
+    let mut daily_sales = vec![0.; days];
+
+    for sale in sales {
+        if sale.timestamp > now {
+            continue;
+        }
+
+        let days_ago = ((now - sale.timestamp) / SECONDS_IN_DAY) as usize;
+
+        if days_ago < days {
+            daily_sales[days - 1 - days_ago] += sale.quantity as f64;
+        }
+    }
+
+    daily_sales
+}
+
+/// Predicts daily demand for the next `horizon` days with additive Holt-Winters,
+/// falling back to simple exponential smoothing when there are less than two weeks of data
+pub fn forecast_demand(daily_sales: &[f64], horizon: usize) -> Vec<f64> {
+    if daily_sales.len() < 2 * SEASON_LENGTH {
+        let Some(&first) = daily_sales.first() else {
+            return vec![0.; horizon];
+        };
+
+        let level = daily_sales.iter().skip(1).fold(first, |level, &sales| {
+            LEVEL_SMOOTHING * sales + (1. - LEVEL_SMOOTHING) * level
+        });
+
+        return vec![level.max(0.); horizon];
+    }
+
+    let first_season_mean = daily_sales[..SEASON_LENGTH].iter().sum::<f64>() / SEASON_LENGTH as f64;
+    let second_season_mean = daily_sales[SEASON_LENGTH..2 * SEASON_LENGTH]
+        .iter()
+        .sum::<f64>()
+        / SEASON_LENGTH as f64;
+
+    let mut level = first_season_mean;
+    let mut trend = (second_season_mean - first_season_mean) / SEASON_LENGTH as f64;
+    let mut seasonal = daily_sales[..SEASON_LENGTH]
+        .iter()
+        .map(|sales| sales - first_season_mean)
+        .collect::<Vec<_>>();
+
+    for (day, &sales) in daily_sales.iter().enumerate().skip(SEASON_LENGTH) {
+        let season = seasonal[day % SEASON_LENGTH];
+        let previous_level = level;
+
+        level =
+            LEVEL_SMOOTHING * (sales - season) + (1. - LEVEL_SMOOTHING) * (previous_level + trend);
+        trend = TREND_SMOOTHING * (level - previous_level) + (1. - TREND_SMOOTHING) * trend;
+        seasonal[day % SEASON_LENGTH] =
+            SEASONAL_SMOOTHING * (sales - level) + (1. - SEASONAL_SMOOTHING) * season;
+    }
+
+    (1..=horizon)
+        .map(|step| {
+            let season = seasonal[(daily_sales.len() + step - 1) % SEASON_LENGTH];
+
+            (level + step as f64 * trend + season).max(0.)
+        })
+        .collect()
+}
+
+/// Average units per day expected to sell over the forecast horizon
+pub fn get_forecast_velocity(sales: &[ItemSaleHistoryUnit], now: u64) -> f64 {
+    let daily_sales = get_daily_sales(sales, now, FORECAST_HISTORY_DAYS as usize);
+    let forecast = forecast_demand(&daily_sales, FORECAST_HORIZON_DAYS);
+
+    forecast.iter().sum::<f64>() / FORECAST_HORIZON_DAYS as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn forecasts_a_constant_series() {
+        let forecast = forecast_demand(&[5.; 28], 7);
+
+        assert_close(&forecast, &[5.; 7]);
+    }
+
+    #[test]
+    fn forecasts_a_weekly_season() {
+        let week = [2., 2., 2., 2., 2., 10., 10.];
+        let daily_sales: Vec<f64> = week.iter().cycle().take(28).copied().collect();
+
+        let forecast = forecast_demand(&daily_sales, 7);
+
+        assert_close(&forecast, &week);
+    }
+
+    #[test]
+    fn smooths_less_than_two_weeks() {
+        let forecast = forecast_demand(&[0., 10.], 3);
+
+        assert_close(&forecast, &[3.; 3]);
+    }
+
+    #[test]
+    fn forecasts_nothing_without_history() {
+        assert_close(&forecast_demand(&[], 7), &[0.; 7]);
+        assert_eq!(get_forecast_velocity(&[], 1_700_000_000), 0.);
+    }
+
+    #[test]
+    fn buckets_sales_by_day() {
+        let now = 100 * SECONDS_IN_DAY;
+        let sale = |timestamp, quantity| ItemSaleHistoryUnit {
+            quantity,
+            price_per_unit: 100,
+            hq: false,
+            timestamp,
+            buyer_name: None,
+        };
+        let sales = [
+            sale(now - 10, 2),
+            sale(now - SECONDS_IN_DAY - 10, 3),
+            sale(now - 5 * SECONDS_IN_DAY, 7),
+            sale(now + 10, 1),
+        ];
+
+        assert_close(&get_daily_sales(&sales, now, 3), &[0., 3., 2.]);
+    }
+}
@@ -0,0 +1,35 @@
+use super::SECONDS_IN_DAY;
+use std::collections::BTreeMap;
+use universalis_sdk::ItemSaleHistoryUnit;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DailySales {
+    /// Unix timestamp of the start of the UTC day
+    pub day: u64,
+    /// Quantity weighted average price per unit
+    pub avg_price: f64,
+    pub quantity: u64,
+}
+
+/// Sales grouped by UTC day, the oldest day first. Days without sales are left out
+pub fn get_daily_sale_history(sales: &[ItemSaleHistoryUnit]) -> Vec<DailySales> {
+    let mut days: BTreeMap<u64, (u64, u64)> = BTreeMap::new();
+
+    for sale in sales {
+        let (spent, quantity) = days
+            .entry(sale.timestamp - sale.timestamp % SECONDS_IN_DAY)
+            .or_default();
+
+        *spent += sale.price_per_unit * sale.quantity;
+        *quantity += sale.quantity;
+    }
+
+    days.into_iter()
+        .filter(|(_, (_, quantity))| *quantity > 0)
+        .map(|(day, (spent, quantity))| DailySales {
+            day,
+            avg_price: spent as f64 / quantity as f64,
+            quantity,
+        })
+        .collect()
+}
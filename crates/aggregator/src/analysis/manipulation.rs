@@ -0,0 +1,215 @@
+use super::SECONDS_IN_DAY;
+use std::collections::HashMap;
+use universalis_sdk::{ItemListing, ItemSaleHistoryUnit};
+
+/// Bits of the `manipulation_flags` SET column of `items_trade_volumes`
+pub const WASH_TRADING: u64 = 1;
+pub const DOMINANT_RETAINER: u64 = 2;
+pub const ARTIFICIAL_FLOOR: u64 = 4;
+
+/// Sales above this share of the median price are considered inflated
+const INFLATED_PRICE_RATIO: f64 = 1.5;
+const MIN_REPEATED_INFLATED_SALES: usize = 3;
+const MIN_DOMINANT_RETAINER_LISTINGS: usize = 4;
+const DOMINANT_RETAINER_SHARE: f64 = 0.6;
+/// Lowest listing above this share of the median price is considered an artificial floor
+const ARTIFICIAL_FLOOR_RATIO: f64 = 2.;
+const MIN_FLOOR_SALES: usize = 3;
+
+pub fn get_manipulation_flags(
+    sales: &[ItemSaleHistoryUnit],
+    listings: &[ItemListing],
+    now: u64,
+) -> u64 {
+    let mut sale_prices = sales
+        .iter()
+        .map(|sale| sale.price_per_unit)
+        .collect::<Vec<_>>();
+    sale_prices.sort_unstable();
+    let median_price = sale_prices
+        .get(sale_prices.len() / 2)
+        .copied()
+        .unwrap_or_default() as f64;
+
+    let mut flags = 0;
+
+    if is_wash_traded(sales, median_price) {
+        flags |= WASH_TRADING;
+    }
+
+    if is_dominated_by_retainer(listings) {
+        flags |= DOMINANT_RETAINER;
+    }
+
+    if has_artificial_floor(sales, listings, median_price, now) {
+        flags |= ARTIFICIAL_FLOOR;
+    }
+
+    flags
+}
+
+/// The same buyer repeatedly paying well above the usual price
+fn is_wash_traded(sales: &[ItemSaleHistoryUnit], median_price: f64) -> bool {
+    let mut buyers_inflated_sales: HashMap<&str, usize> = HashMap::new();
+    let mut inflated_sales = 0;
+
+    for sale in sales {
+        if (sale.price_per_unit as f64) <= median_price * INFLATED_PRICE_RATIO {
+            continue;
+        }
+
+        inflated_sales += 1;
+
+        if let Some(buyer_name) = &sale.buyer_name {
+            *buyers_inflated_sales.entry(buyer_name).or_default() += 1;
+        }
+    }
+
+    buyers_inflated_sales.values().any(|&buyer_sales| {
+        buyer_sales >= MIN_REPEATED_INFLATED_SALES && buyer_sales * 2 >= inflated_sales
+    })
+}
+
+/// A single retainer holding most of the listings
+fn is_dominated_by_retainer(listings: &[ItemListing]) -> bool {
+    if listings.len() < MIN_DOMINANT_RETAINER_LISTINGS {
+        return false;
+    }
+
+    let mut retainers_listings: HashMap<&str, usize> = HashMap::new();
+
+    for listing in listings {
+        *retainers_listings.entry(&listing.retainer_id).or_default() += 1;
+    }
+
+    retainers_listings.values().any(|&retainer_listings| {
+        retainer_listings as f64 / listings.len() as f64 > DOMINANT_RETAINER_SHARE
+    })
+}
+
+/// The cheap listings were bought out recently and everything left is listed far above sale prices
+fn has_artificial_floor(
+    sales: &[ItemSaleHistoryUnit],
+    listings: &[ItemListing],
+    median_price: f64,
+    now: u64,
+) -> bool {
+    if sales.len() < MIN_FLOOR_SALES {
+        return false;
+    }
+
+    let Some(lowest_listing) = listings.iter().min_by_key(|listing| listing.price_per_unit) else {
+        return false;
+    };
+
+    let recent_buyout = sales
+        .iter()
+        .any(|sale| sale.timestamp + SECONDS_IN_DAY >= now);
+
+    recent_buyout && lowest_listing.price_per_unit as f64 > median_price * ARTIFICIAL_FLOOR_RATIO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn sale(price_per_unit: u64, buyer_name: &str, age: u64) -> ItemSaleHistoryUnit {
+        ItemSaleHistoryUnit {
+            quantity: 1,
+            price_per_unit,
+            hq: false,
+            timestamp: NOW - age,
+            buyer_name: Some(buyer_name.to_string()),
+        }
+    }
+
+    fn listing(retainer_id: &str, price_per_unit: u64) -> ItemListing {
+        ItemListing {
+            price_per_unit,
+            quantity: 1,
+            hq: false,
+            retainer_id: retainer_id.to_string(),
+            retainer_name: String::new(),
+            last_review_time: NOW,
+        }
+    }
+
+    #[test]
+    fn flags_match_the_set_column() {
+        let migration = include_str!(
+            "../../../../migrations/20230506102231_add_manipulation_flags_to_items_trade_volumes.sql"
+        );
+        let (_, members) = migration.split_once("SET (").unwrap();
+        let (members, _) = members.split_once(')').unwrap();
+        let members: Vec<&str> = members
+            .split(',')
+            .map(|member| member.trim().trim_matches('\''))
+            .collect();
+
+        assert_eq!(
+            members,
+            ["wash_trading", "dominant_retainer", "artificial_floor"]
+        );
+        assert_eq!(
+            [WASH_TRADING, DOMINANT_RETAINER, ARTIFICIAL_FLOOR],
+            [1 << 0, 1 << 1, 1 << 2]
+        );
+    }
+
+    #[test]
+    fn flags_wash_trading() {
+        let mut sales: Vec<_> = (0..4)
+            .map(|_| sale(100, "buyer", SECONDS_IN_DAY * 3))
+            .collect();
+        sales.extend((0..3).map(|_| sale(200, "friend", SECONDS_IN_DAY * 3)));
+
+        assert_eq!(get_manipulation_flags(&sales, &[], NOW), WASH_TRADING);
+    }
+
+    #[test]
+    fn flags_a_dominant_retainer() {
+        let listings = [
+            listing("a", 100),
+            listing("a", 110),
+            listing("a", 120),
+            listing("b", 130),
+        ];
+
+        assert_eq!(
+            get_manipulation_flags(&[], &listings, NOW),
+            DOMINANT_RETAINER
+        );
+    }
+
+    #[test]
+    fn flags_an_artificial_floor() {
+        let sales = [
+            sale(100, "a", 60),
+            sale(100, "b", SECONDS_IN_DAY * 2),
+            sale(100, "c", SECONDS_IN_DAY * 3),
+        ];
+        let listings = [listing("a", 250), listing("b", 300)];
+
+        assert_eq!(
+            get_manipulation_flags(&sales, &listings, NOW),
+            ARTIFICIAL_FLOOR
+        );
+        // Without a recent buyout the listings may just be overpriced
+        let old_sales = [
+            sale(100, "a", SECONDS_IN_DAY * 2),
+            sale(100, "b", SECONDS_IN_DAY * 2),
+            sale(100, "c", SECONDS_IN_DAY * 3),
+        ];
+        assert_eq!(get_manipulation_flags(&old_sales, &listings, NOW), 0);
+    }
+
+    #[test]
+    fn leaves_a_normal_market_unflagged() {
+        let sales = [sale(100, "a", 60), sale(110, "b", 120), sale(90, "c", 180)];
+        let listings = [listing("a", 105), listing("b", 110), listing("c", 120)];
+
+        assert_eq!(get_manipulation_flags(&sales, &listings, NOW), 0);
+    }
+}
@@ -0,0 +1,44 @@
+mod forecast;
+mod history;
+mod manipulation;
+mod price_advice;
+mod purchase_plan;
+mod route;
+mod saturation;
+mod search;
+mod stats;
+mod timing;
+mod trend;
+
+pub(crate) use forecast::*;
+pub(crate) use history::*;
+pub(crate) use manipulation::*;
+pub(crate) use price_advice::*;
+pub(crate) use purchase_plan::*;
+pub(crate) use route::*;
+pub(crate) use saturation::*;
+pub(crate) use search::*;
+pub(crate) use stats::*;
+pub(crate) use timing::*;
+pub(crate) use trend::*;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+/// Current unix timestamp in seconds, the unit Universalis uses for sales and listings
+pub fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Price the buyer pays for a listing including the market tax. Sale history doesn't say which
+/// city a sale was listed in, so the most taxed city's rate is used rather than an average that
+/// could overstate the profit
+pub fn get_purchase_price(price: f64, cities_rates: impl IntoIterator<Item = u64>) -> f64 {
+    let tax_rate = cities_rates.into_iter().max().unwrap_or_default();
+
+    price * (1. + tax_rate as f64 / 100.)
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use universalis_sdk::{ItemListing, ItemSaleHistoryUnit};
+
+/// Listings cheaper than this share of the median sale price are treated as dumps and not chased
+const DUMP_PRICE_RATIO: f64 = 0.7;
+
+#[derive(Debug, Clone)]
+pub struct StackSizeShare {
+    pub quantity: u64,
+    pub sales: usize,
+    pub share: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceAdvice {
+    pub lowest_listing_price: Option<u64>,
+    pub median_sale_price: Option<u64>,
+    pub recommended_price: u64,
+    pub stack_sizes: Vec<StackSizeShare>,
+}
+
+pub fn get_price_advice(
+    listings: &[ItemListing],
+    sales: &[ItemSaleHistoryUnit],
+) -> Option<PriceAdvice> {
+    let mut sale_prices = sales
+        .iter()
+        .map(|sale| sale.price_per_unit)
+        .collect::<Vec<_>>();
+    sale_prices.sort_unstable();
+    let median_sale_price = sale_prices.get(sale_prices.len() / 2).copied();
+
+    let mut listing_prices = listings
+        .iter()
+        .map(|listing| listing.price_per_unit)
+        .collect::<Vec<_>>();
+    listing_prices.sort_unstable();
+    let lowest_listing_price = listing_prices.first().copied();
+
+    let dump_price = median_sale_price
+        .map(|price| (price as f64 * DUMP_PRICE_RATIO) as u64)
+        .unwrap_or_default();
+
+    let recommended_price = match listing_prices.iter().find(|&&price| price >= dump_price) {
+        Some(price) => price.saturating_sub(1).max(1),
+        None => median_sale_price?,
+    };
+
+    Some(PriceAdvice {
+        lowest_listing_price,
+        median_sale_price,
+        recommended_price,
+        stack_sizes: get_stack_sizes(sales),
+    })
+}
+
+/// Histogram of sold stack sizes, the most sold first
+pub fn get_stack_sizes(sales: &[ItemSaleHistoryUnit]) -> Vec<StackSizeShare> {
+    let mut stack_sizes_sales: HashMap<u64, usize> = HashMap::new();
+
+    for sale in sales {
+        *stack_sizes_sales.entry(sale.quantity).or_default() += 1;
+    }
+
+    let mut stack_sizes = stack_sizes_sales
+        .into_iter()
+        .map(|(quantity, stack_sales)| StackSizeShare {
+            quantity,
+            sales: stack_sales,
+            share: stack_sales as f64 / sales.len() as f64,
+        })
+        .collect::<Vec<_>>();
+
+    stack_sizes.sort_by(|a, b| b.sales.cmp(&a.sales).then(a.quantity.cmp(&b.quantity)));
+
+    stack_sizes
+}
+
+/// Days until `quantity` units listed at the lowest price are sold at the given daily velocity
+pub fn estimate_days_to_sell(quantity: u64, velocity: f64) -> Option<f64> {
+    if velocity <= 0. {
+        return None;
+    }
+
+    Some(quantity as f64 / velocity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(price_per_unit: u64) -> ItemListing {
+        ItemListing {
+            price_per_unit,
+            quantity: 1,
+            hq: false,
+            retainer_id: String::new(),
+            retainer_name: String::new(),
+            last_review_time: 0,
+        }
+    }
+
+    fn sale(price_per_unit: u64, quantity: u64) -> ItemSaleHistoryUnit {
+        ItemSaleHistoryUnit {
+            quantity,
+            price_per_unit,
+            hq: false,
+            timestamp: 0,
+            buyer_name: None,
+        }
+    }
+
+    fn sales() -> Vec<ItemSaleHistoryUnit> {
+        vec![sale(1100, 1), sale(900, 1), sale(1000, 1)]
+    }
+
+    #[test]
+    fn undercuts_the_lowest_listing_by_one_gil() {
+        let price_advice = get_price_advice(&[listing(1200), listing(980)], &sales()).unwrap();
+
+        assert_eq!(price_advice.lowest_listing_price, Some(980));
+        assert_eq!(price_advice.median_sale_price, Some(1000));
+        assert_eq!(price_advice.recommended_price, 979);
+    }
+
+    #[test]
+    fn skips_dump_listings() {
+        // Dumps are listings under 70% of the 1000 gil median
+        let listings = [listing(100), listing(699), listing(700), listing(900)];
+
+        let price_advice = get_price_advice(&listings, &sales()).unwrap();
+
+        assert_eq!(price_advice.lowest_listing_price, Some(100));
+        assert_eq!(price_advice.recommended_price, 699);
+    }
+
+    #[test]
+    fn falls_back_to_the_median_when_every_listing_is_a_dump() {
+        let price_advice = get_price_advice(&[listing(100)], &sales()).unwrap();
+
+        assert_eq!(price_advice.recommended_price, 1000);
+    }
+
+    #[test]
+    fn needs_listings_or_sales() {
+        assert!(get_price_advice(&[], &[]).is_none());
+        assert_eq!(
+            get_price_advice(&[listing(1)], &[])
+                .unwrap()
+                .recommended_price,
+            1
+        );
+    }
+
+    #[test]
+    fn orders_stack_sizes_by_sales() {
+        let sales = [sale(10, 99), sale(10, 1), sale(10, 99), sale(10, 20)];
+
+        let stack_sizes = get_stack_sizes(&sales);
+
+        assert_eq!(
+            stack_sizes
+                .iter()
+                .map(|stack_size| (stack_size.quantity, stack_size.sales))
+                .collect::<Vec<_>>(),
+            vec![(99, 2), (1, 1), (20, 1)]
+        );
+        assert_eq!(stack_sizes[0].share, 0.5);
+    }
+}
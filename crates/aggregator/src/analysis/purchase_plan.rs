@@ -0,0 +1,404 @@
+use std::mem::size_of;
+
+/// Bytes the plan may use for its `(slots, budget steps)` capacities. Budgets with more steps
+/// than fit are solved on a coarser step
+const MAX_PLAN_BYTES: usize = 256 << 20;
+/// Capacities alive while an item is added: the previous item's, the bought stacks of both
+/// branches and the partial stack of the second one
+const LIVE_CAPACITIES: usize = 4;
+
+#[derive(Debug, Copy, Clone)]
+pub struct PurchaseCandidate {
+    pub item_id: u64,
+    /// Gil paid per unit including market tax
+    pub unit_cost: f64,
+    pub unit_profit: f64,
+    /// Units the home world can absorb over the planning horizon
+    pub max_quantity: u64,
+    pub stack_size: u64,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PlannedPurchase {
+    pub item_id: u64,
+    pub quantity: u64,
+    pub cost: f64,
+    pub expected_profit: f64,
+}
+
+/// Units of one item that are either bought together or not at all
+#[derive(Debug, Copy, Clone)]
+struct Bundle {
+    quantity: u32,
+    slots: usize,
+    steps: usize,
+    profit: f64,
+}
+
+/// Best profit per `(used slots, used budget steps)` capacity, capacities no purchase fits
+/// are negative infinity. Quantities are the units of the latest item on the best path
+#[derive(Clone)]
+struct Capacities {
+    slots: usize,
+    width: usize,
+    profits: Vec<f64>,
+    quantities: Vec<u32>,
+}
+
+impl Capacities {
+    fn new(slots: usize, width: usize) -> Self {
+        Self {
+            slots,
+            width,
+            profits: vec![0.; (slots + 1) * width],
+            quantities: vec![0; (slots + 1) * width],
+        }
+    }
+
+    fn index(&self, used_slots: usize, used_steps: usize) -> usize {
+        used_slots * self.width + used_steps
+    }
+
+    /// Buys the bundle on top of every capacity it is more profitable with
+    fn add_bundle(&mut self, bundle: Bundle) {
+        // Walking capacities downwards keeps the bundle bought at most once
+        for used_slots in (bundle.slots..=self.slots).rev() {
+            for used_steps in (bundle.steps..self.width).rev() {
+                let from = self.index(used_slots - bundle.slots, used_steps - bundle.steps);
+                let to = self.index(used_slots, used_steps);
+                let profit = self.profits[from] + bundle.profit;
+
+                if profit > self.profits[to] {
+                    self.profits[to] = profit;
+                    self.quantities[to] = self.quantities[from] + bundle.quantity;
+                }
+            }
+        }
+    }
+
+    /// Capacities with the bundle always bought
+    fn with_bundle(&self, bundle: Bundle) -> Self {
+        let mut capacities = Self {
+            slots: self.slots,
+            width: self.width,
+            profits: vec![f64::NEG_INFINITY; self.profits.len()],
+            quantities: vec![0; self.quantities.len()],
+        };
+
+        for used_slots in bundle.slots..=self.slots {
+            for used_steps in bundle.steps..self.width {
+                let from = self.index(used_slots - bundle.slots, used_steps - bundle.steps);
+                let to = self.index(used_slots, used_steps);
+
+                capacities.profits[to] = self.profits[from] + bundle.profit;
+                capacities.quantities[to] = self.quantities[from] + bundle.quantity;
+            }
+        }
+
+        capacities
+    }
+
+    fn merge(&mut self, other: &Capacities) {
+        for index in 0..self.profits.len() {
+            if other.profits[index] > self.profits[index] {
+                self.profits[index] = other.profits[index];
+                self.quantities[index] = other.quantities[index];
+            }
+        }
+    }
+}
+
+/// Costs and slots of one candidate's units on the budget steps
+struct CandidatePricing {
+    unit_cost: u64,
+    unit_profit: f64,
+    stack_size: u64,
+    step: u64,
+}
+
+impl CandidatePricing {
+    fn bundle(&self, quantity: u64, slots: u64) -> Bundle {
+        Bundle {
+            quantity: quantity as u32,
+            slots: slots as usize,
+            steps: (quantity * self.unit_cost).div_ceil(self.step) as usize,
+            profit: quantity as f64 * self.unit_profit,
+        }
+    }
+
+    /// Buys `fixed_stacks` full stacks, up to `optional_stacks` more and a partial stack of up
+    /// to `partial_units`, the partial stack takes its slot once however many units it has
+    fn buy_stacks(
+        &self,
+        capacities: &Capacities,
+        fixed_stacks: u64,
+        optional_stacks: u64,
+        partial_units: u64,
+    ) -> Capacities {
+        let mut bought = if fixed_stacks > 0 {
+            capacities.with_bundle(self.bundle(fixed_stacks * self.stack_size, fixed_stacks))
+        } else {
+            capacities.clone()
+        };
+
+        for stacks in binary_split(optional_stacks) {
+            bought.add_bundle(self.bundle(stacks * self.stack_size, stacks));
+        }
+
+        if partial_units > 0 {
+            let mut partial = bought.with_bundle(self.bundle(0, 1));
+
+            for units in binary_split(partial_units) {
+                partial.add_bundle(self.bundle(units, 0));
+            }
+
+            bought.merge(&partial);
+        }
+
+        bought
+    }
+}
+
+/// Picks item quantities maximising the expected profit within the gil budget and the
+/// inventory slots, paying whole gil per unit. Solved as a knapsack over `(slots, budget
+/// steps)` where a step is the greatest common divisor of the unit costs, which is exact while
+/// the capacities and the quantities kept per candidate to rebuild the plan fit in
+/// `MAX_PLAN_BYTES`. Larger budgets round bundle costs up to a coarser step and may leave gil
+/// unspent
+pub fn plan_purchases(
+    candidates: &[PurchaseCandidate],
+    budget: f64,
+    slots: usize,
+) -> Vec<PlannedPurchase> {
+    if budget <= 0. || slots == 0 {
+        return Vec::new();
+    }
+
+    let budget = budget.floor() as u64;
+    let candidates: Vec<(&PurchaseCandidate, u64)> = candidates
+        .iter()
+        .filter(|candidate| candidate.unit_profit > 0. && candidate.unit_cost > 0.)
+        .map(|candidate| (candidate, candidate.unit_cost.ceil() as u64))
+        .collect();
+
+    let unit = candidates
+        .iter()
+        .fold(0, |unit, (_, unit_cost)| gcd(unit, *unit_cost));
+
+    if unit == 0 {
+        return Vec::new();
+    }
+
+    let state_bytes = LIVE_CAPACITIES * (size_of::<f64>() + size_of::<u32>())
+        + candidates.len() * size_of::<u32>();
+    let max_steps = (MAX_PLAN_BYTES / state_bytes / (slots + 1)).max(1) as u64;
+    let step = unit * (budget / unit).div_ceil(max_steps).max(1);
+    let mut capacities = Capacities::new(slots, (budget / step) as usize + 1);
+    let mut items_quantities = Vec::with_capacity(candidates.len());
+
+    for (candidate, unit_cost) in &candidates {
+        let pricing = CandidatePricing {
+            unit_cost: *unit_cost,
+            unit_profit: candidate.unit_profit,
+            stack_size: candidate.stack_size.max(1),
+            step,
+        };
+        let quantity = candidate
+            .max_quantity
+            .min(budget / unit_cost)
+            .min(slots as u64 * pricing.stack_size)
+            .min(u32::MAX as u64);
+        let full_stacks = quantity / pricing.stack_size;
+
+        // Any partial stack next to fewer than all full stacks, or only the leftover units
+        // next to all of them, so no quantity above the maximum is bought
+        let mut bought =
+            pricing.buy_stacks(&capacities, full_stacks, 0, quantity % pricing.stack_size);
+        if full_stacks > 0 {
+            bought.merge(&pricing.buy_stacks(
+                &capacities,
+                0,
+                full_stacks - 1,
+                pricing.stack_size - 1,
+            ));
+        }
+
+        items_quantities.push(bought.quantities);
+        capacities = Capacities {
+            quantities: vec![0; bought.profits.len()],
+            ..bought
+        };
+    }
+
+    let mut planned_purchases = Vec::new();
+    let mut used_slots = slots;
+    let mut used_steps = capacities.width - 1;
+
+    for ((candidate, unit_cost), quantities) in candidates.iter().zip(&items_quantities).rev() {
+        let quantity = quantities[capacities.index(used_slots, used_steps)] as u64;
+
+        if quantity == 0 {
+            continue;
+        }
+
+        // Bundles are rounded up one by one, so the path may have used more steps. Leaving the
+        // earlier items more budget only finds them a plan at least as profitable
+        used_slots -= quantity.div_ceil(candidate.stack_size.max(1)) as usize;
+        used_steps -= (quantity * unit_cost).div_ceil(step) as usize;
+
+        planned_purchases.push(PlannedPurchase {
+            item_id: candidate.item_id,
+            quantity,
+            cost: quantity as f64 * candidate.unit_cost,
+            expected_profit: quantity as f64 * candidate.unit_profit,
+        });
+    }
+
+    planned_purchases.reverse();
+
+    planned_purchases
+}
+
+/// Powers of two and a remainder that every count up to `count` is a sum of
+fn binary_split(count: u64) -> Vec<u64> {
+    let mut parts = Vec::new();
+    let mut remaining = count;
+    let mut part = 1;
+
+    while remaining > 0 {
+        let count = part.min(remaining);
+        parts.push(count);
+        remaining -= count;
+        part *= 2;
+    }
+
+    parts
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        item_id: u64,
+        unit_cost: f64,
+        unit_profit: f64,
+        max_quantity: u64,
+        stack_size: u64,
+    ) -> PurchaseCandidate {
+        PurchaseCandidate {
+            item_id,
+            unit_cost,
+            unit_profit,
+            max_quantity,
+            stack_size,
+        }
+    }
+
+    fn brute_force(candidates: &[PurchaseCandidate], budget: f64, slots: usize) -> f64 {
+        let Some((candidate, rest)) = candidates.split_first() else {
+            return 0.;
+        };
+
+        (0..=candidate.max_quantity)
+            .filter_map(|quantity| {
+                let cost = quantity as f64 * candidate.unit_cost;
+                let used_slots = quantity.div_ceil(candidate.stack_size) as usize;
+
+                (cost <= budget && used_slots <= slots).then(|| {
+                    quantity as f64 * candidate.unit_profit
+                        + brute_force(rest, budget - cost, slots - used_slots)
+                })
+            })
+            .fold(0., f64::max)
+    }
+
+    fn assert_optimal(candidates: &[PurchaseCandidate], budget: f64, slots: usize) {
+        let planned_purchases = plan_purchases(candidates, budget, slots);
+
+        let cost: f64 = planned_purchases.iter().map(|purchase| purchase.cost).sum();
+        let profit: f64 = planned_purchases
+            .iter()
+            .map(|purchase| purchase.expected_profit)
+            .sum();
+        let used_slots: u64 = planned_purchases
+            .iter()
+            .map(|purchase| {
+                let candidate = candidates
+                    .iter()
+                    .find(|candidate| candidate.item_id == purchase.item_id)
+                    .unwrap();
+
+                assert!(purchase.quantity <= candidate.max_quantity);
+                purchase.quantity.div_ceil(candidate.stack_size)
+            })
+            .sum();
+
+        assert!(cost <= budget, "{candidates:?} {budget} {slots}");
+        assert!(
+            used_slots as usize <= slots,
+            "{candidates:?} {budget} {slots}"
+        );
+        assert!(
+            (profit - brute_force(candidates, budget, slots)).abs() < 1e-6,
+            "{candidates:?} {budget} {slots}: {planned_purchases:?}"
+        );
+    }
+
+    #[test]
+    fn buys_a_partial_stack() {
+        let candidates = [
+            candidate(1, 185., 79., 7, 4),
+            candidate(2, 400., 138., 1, 1),
+        ];
+
+        assert_optimal(&candidates, 813., 5);
+    }
+
+    #[test]
+    fn respects_the_slots() {
+        let candidates = [
+            candidate(1, 10., 5., 999, 999),
+            candidate(2, 10., 1., 10, 1),
+        ];
+        let planned_purchases = plan_purchases(&candidates, 100000., 1);
+
+        assert_eq!(planned_purchases.len(), 1);
+        assert_eq!(planned_purchases[0].quantity, 999);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut random = |max: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % (max + 1)
+        };
+
+        for _ in 0..300 {
+            let candidates: Vec<PurchaseCandidate> = (0..1 + random(3))
+                .map(|item_id| {
+                    candidate(
+                        item_id,
+                        (1 + random(299)) as f64,
+                        (1 + random(99)) as f64,
+                        random(8),
+                        [1, 2, 3, 4, 99][random(4) as usize],
+                    )
+                })
+                .collect();
+
+            assert_optimal(&candidates, random(1000) as f64, 1 + random(5) as usize);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Copy, Clone)]
+pub struct ShoppingListItem {
+    pub item_id: u64,
+    pub quantity: u64,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RoutePurchase {
+    pub item_id: u64,
+    pub quantity: u64,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorldVisit {
+    pub world_id: u64,
+    pub purchases: Vec<RoutePurchase>,
+    pub spend: f64,
+}
+
+/// Chooses which worlds to visit for the shopping list, trading the gil a transfer to a world
+/// is worth against buying at a slightly higher price on an already visited world.
+/// `unit_costs` maps item ids to the unit cost per world id and `transfer_costs` world ids to
+/// their transfer cost, worlds without a transfer cost can't be traveled to. Items that can't be
+/// bought on any reachable world are skipped.
+/// Visits start on the home world when anything is bought there, then go by descending spend
+pub fn plan_route(
+    shopping_list: &[ShoppingListItem],
+    unit_costs: &HashMap<u64, HashMap<u64, f64>>,
+    home_world_id: u64,
+    transfer_costs: &HashMap<u64, f64>,
+) -> Vec<WorldVisit> {
+    let shopping_list: Vec<(&ShoppingListItem, HashMap<u64, f64>)> = shopping_list
+        .iter()
+        .filter_map(|item| {
+            let item_unit_costs: HashMap<u64, f64> = unit_costs
+                .get(&item.item_id)?
+                .iter()
+                .filter(|(world_id, _)| transfer_costs.contains_key(world_id))
+                .map(|(&world_id, &unit_cost)| (world_id, unit_cost))
+                .collect();
+
+            (!item_unit_costs.is_empty()).then_some((item, item_unit_costs))
+        })
+        .collect();
+
+    let worlds_ids: BTreeSet<u64> = shopping_list
+        .iter()
+        .flat_map(|(_, item_unit_costs)| item_unit_costs.keys().copied())
+        .collect();
+
+    let route_cost = |visited: &BTreeSet<u64>| -> Option<f64> {
+        let mut cost = visited
+            .iter()
+            .filter(|&&world_id| world_id != home_world_id)
+            .filter_map(|world_id| transfer_costs.get(world_id))
+            .sum::<f64>();
+
+        for (item, item_unit_costs) in &shopping_list {
+            let (_, unit_cost) = cheapest_visited_world(item_unit_costs, visited)?;
+            cost += unit_cost * item.quantity as f64;
+        }
+
+        Some(cost)
+    };
+
+    // Cover every item first, preferring worlds that have the most of the still missing items
+    let mut visited = BTreeSet::new();
+
+    loop {
+        let missing_items: Vec<_> = shopping_list
+            .iter()
+            .filter(|(_, item_unit_costs)| {
+                cheapest_visited_world(item_unit_costs, &visited).is_none()
+            })
+            .collect();
+
+        if missing_items.is_empty() {
+            break;
+        }
+
+        let world_id = worlds_ids
+            .iter()
+            .copied()
+            .filter(|world_id| !visited.contains(world_id))
+            .max_by(|a, b| {
+                let covered = |world_id: &u64| {
+                    missing_items
+                        .iter()
+                        .filter(|(_, item_unit_costs)| item_unit_costs.contains_key(world_id))
+                        .count()
+                };
+
+                covered(a).cmp(&covered(b))
+            });
+
+        match world_id {
+            Some(world_id) => visited.insert(world_id),
+            None => break,
+        };
+    }
+
+    // Then add or drop single worlds while that lowers the prices plus transfers
+    let Some(mut cost) = route_cost(&visited) else {
+        return Vec::new();
+    };
+
+    loop {
+        let best_move = worlds_ids
+            .iter()
+            .filter_map(|world_id| {
+                let mut candidate = visited.clone();
+
+                if !candidate.remove(world_id) {
+                    candidate.insert(*world_id);
+                }
+
+                Some((route_cost(&candidate)?, candidate))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        match best_move {
+            Some((candidate_cost, candidate)) if candidate_cost < cost => {
+                cost = candidate_cost;
+                visited = candidate;
+            }
+            _ => break,
+        }
+    }
+
+    let mut world_visits: HashMap<u64, WorldVisit> = HashMap::new();
+
+    for (item, item_unit_costs) in &shopping_list {
+        let Some((world_id, unit_cost)) = cheapest_visited_world(item_unit_costs, &visited) else {
+            continue;
+        };
+
+        let world_visit = world_visits.entry(world_id).or_insert(WorldVisit {
+            world_id,
+            purchases: Vec::new(),
+            spend: 0.,
+        });
+
+        world_visit.purchases.push(RoutePurchase {
+            item_id: item.item_id,
+            quantity: item.quantity,
+            unit_cost,
+        });
+        world_visit.spend += unit_cost * item.quantity as f64;
+    }
+
+    let mut world_visits: Vec<WorldVisit> = world_visits.into_values().collect();
+    world_visits.sort_by(|a, b| {
+        (b.world_id == home_world_id)
+            .cmp(&(a.world_id == home_world_id))
+            .then(b.spend.total_cmp(&a.spend))
+    });
+
+    world_visits
+}
+
+fn cheapest_visited_world(
+    item_unit_costs: &HashMap<u64, f64>,
+    visited: &BTreeSet<u64>,
+) -> Option<(u64, f64)> {
+    item_unit_costs
+        .iter()
+        .filter(|(world_id, _)| visited.contains(world_id))
+        .map(|(&world_id, &unit_cost)| (world_id, unit_cost))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOME_WORLD_ID: u64 = 1;
+
+    fn item(item_id: u64, quantity: u64) -> ShoppingListItem {
+        ShoppingListItem { item_id, quantity }
+    }
+
+    fn unit_costs(costs: &[(u64, &[(u64, f64)])]) -> HashMap<u64, HashMap<u64, f64>> {
+        costs
+            .iter()
+            .map(|(item_id, world_costs)| (*item_id, world_costs.iter().copied().collect()))
+            .collect()
+    }
+
+    fn transfer_costs() -> HashMap<u64, f64> {
+        HashMap::from([(HOME_WORLD_ID, 0.), (2, 10000.), (3, 10000.)])
+    }
+
+    fn visited_worlds(world_visits: &[WorldVisit]) -> Vec<u64> {
+        world_visits
+            .iter()
+            .map(|world_visit| world_visit.world_id)
+            .collect()
+    }
+
+    #[test]
+    fn skips_a_transfer_worth_more_than_the_savings() {
+        let unit_costs = unit_costs(&[(10, &[(2, 100.), (3, 105.)]), (20, &[(3, 200.)])]);
+
+        let world_visits = plan_route(
+            &[item(10, 10), item(20, 1)],
+            &unit_costs,
+            HOME_WORLD_ID,
+            &transfer_costs(),
+        );
+
+        assert_eq!(visited_worlds(&world_visits), [3]);
+        assert_eq!(world_visits[0].spend, 1250.);
+    }
+
+    #[test]
+    fn adds_a_transfer_worth_less_than_the_savings() {
+        let unit_costs = unit_costs(&[
+            (10, &[(HOME_WORLD_ID, 1000.), (2, 100.)]),
+            (20, &[(HOME_WORLD_ID, 50.), (2, 60.)]),
+        ]);
+
+        let world_visits = plan_route(
+            &[item(10, 100), item(20, 100)],
+            &unit_costs,
+            HOME_WORLD_ID,
+            &transfer_costs(),
+        );
+
+        // The home world goes first even though less is spent there
+        assert_eq!(visited_worlds(&world_visits), [HOME_WORLD_ID, 2]);
+        assert_eq!(world_visits[0].purchases[0].item_id, 20);
+        assert_eq!(world_visits[1].purchases[0].item_id, 10);
+    }
+
+    #[test]
+    fn skips_items_only_sold_on_unreachable_worlds() {
+        let unit_costs = unit_costs(&[(10, &[(4, 100.)]), (20, &[(2, 100.)])]);
+
+        let world_visits = plan_route(
+            &[item(10, 1), item(20, 1)],
+            &unit_costs,
+            HOME_WORLD_ID,
+            &transfer_costs(),
+        );
+
+        assert_eq!(visited_worlds(&world_visits), [2]);
+        assert_eq!(world_visits[0].purchases.len(), 1);
+        assert!(plan_route(
+            &[item(10, 1)],
+            &unit_costs,
+            HOME_WORLD_ID,
+            &transfer_costs()
+        )
+        .is_empty());
+    }
+}
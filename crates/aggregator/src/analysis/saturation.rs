@@ -0,0 +1,137 @@
+use super::SECONDS_IN_DAY;
+use std::collections::HashSet;
+use universalis_sdk::ItemListingsMapping;
+
+/// Items without recent sales are treated as selling once a month
+const MIN_VELOCITY: f64 = 1. / 30.;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ListingSaturation {
+    pub competing_retainers: u64,
+    pub days_of_supply: f64,
+    pub lowest_listing_changes: u64,
+    pub saturation_score: f64,
+}
+
+/// Measures how crowded the market for an item is, higher `saturation_score` means more competition
+pub fn get_listing_saturation(
+    item_listings: &ItemListingsMapping,
+    velocity: f64,
+    now: u64,
+) -> ListingSaturation {
+    let competing_retainers = item_listings
+        .listings
+        .iter()
+        .map(|listing| &listing.retainer_id)
+        .collect::<HashSet<_>>()
+        .len() as u64;
+
+    let units_for_sale = if item_listings.units_for_sale > 0 {
+        item_listings.units_for_sale
+    } else {
+        item_listings
+            .listings
+            .iter()
+            .map(|listing| listing.quantity)
+            .sum()
+    };
+
+    let days_of_supply = units_for_sale as f64 / velocity.max(MIN_VELOCITY);
+
+    // Walking listings from the oldest one, every listing cheaper than all listings posted
+    // before it was the lowest listing at the time it was posted
+    let mut listings = item_listings.listings.iter().collect::<Vec<_>>();
+    listings.sort_by_key(|listing| listing.last_review_time);
+
+    let mut lowest_price = u64::MAX;
+    let mut lowest_listing_changes = 0;
+
+    for listing in listings {
+        if listing.price_per_unit < lowest_price {
+            lowest_price = listing.price_per_unit;
+
+            if listing.last_review_time + SECONDS_IN_DAY >= now {
+                lowest_listing_changes += 1;
+            }
+        }
+    }
+
+    let saturation_score = competing_retainers as f64
+        * (1. + lowest_listing_changes as f64)
+        * (1. + days_of_supply).ln();
+
+    ListingSaturation {
+        competing_retainers,
+        days_of_supply,
+        lowest_listing_changes,
+        saturation_score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use universalis_sdk::ItemListing;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn listing(retainer_id: &str, price_per_unit: u64, quantity: u64, age: u64) -> ItemListing {
+        ItemListing {
+            price_per_unit,
+            quantity,
+            hq: false,
+            retainer_id: retainer_id.to_string(),
+            retainer_name: String::new(),
+            last_review_time: NOW - age,
+        }
+    }
+
+    fn item_listings(listings: Vec<ItemListing>, units_for_sale: u64) -> ItemListingsMapping {
+        ItemListingsMapping {
+            listings,
+            units_for_sale,
+            sale_velocity: 0.,
+            last_upload_time: 0,
+        }
+    }
+
+    #[test]
+    fn counts_recent_undercuts() {
+        let item_listings = item_listings(
+            vec![
+                // Lowest two days ago, then undercut twice today and outbid once
+                listing("a", 1000, 5, 2 * SECONDS_IN_DAY),
+                listing("b", 900, 5, 3600),
+                listing("a", 950, 5, 1800),
+                listing("c", 800, 5, 600),
+            ],
+            0,
+        );
+
+        let saturation = get_listing_saturation(&item_listings, 4., NOW);
+
+        assert_eq!(saturation.competing_retainers, 3);
+        assert_eq!(saturation.lowest_listing_changes, 2);
+        assert_eq!(saturation.days_of_supply, 5.);
+        assert!((saturation.saturation_score - 3. * 3. * 6f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn prefers_reported_units_for_sale() {
+        let item_listings = item_listings(vec![listing("a", 1000, 5, 0)], 60);
+
+        let saturation = get_listing_saturation(&item_listings, 0., NOW);
+
+        // Without sales the item is treated as selling once a month
+        assert!((saturation.days_of_supply - 1800.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_market_is_not_saturated() {
+        let saturation = get_listing_saturation(&item_listings(Vec::new(), 0), 1., NOW);
+
+        assert_eq!(saturation.competing_retainers, 0);
+        assert_eq!(saturation.lowest_listing_changes, 0);
+        assert_eq!(saturation.saturation_score, 0.);
+    }
+}
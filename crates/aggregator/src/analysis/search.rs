@@ -0,0 +1,89 @@
+use strsim::jaro_winkler;
+
+/// How well an item name matches a search query, from 0 to 1. Names containing the query
+/// score above 0.9, everything else is scored by the typo-tolerant Jaro-Winkler similarity
+/// of the whole name or of its words
+pub fn get_name_match_score(query: &str, name: &str) -> f64 {
+    let query = query.trim().to_lowercase();
+    let name = name.to_lowercase();
+
+    if query.is_empty() {
+        return 0.;
+    }
+
+    if name == query {
+        return 1.;
+    }
+
+    if name.contains(&query) {
+        return 0.9 + 0.09 * query.len() as f64 / name.len() as f64;
+    }
+
+    let name_words: Vec<&str> = name.split_whitespace().collect();
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+
+    let words_similarity = query_words
+        .iter()
+        .map(|query_word| {
+            name_words
+                .iter()
+                .map(|name_word| jaro_winkler(query_word, name_word))
+                .fold(0., f64::max)
+        })
+        .sum::<f64>()
+        / query_words.len() as f64;
+
+    0.9 * words_similarity.max(jaro_winkler(&query, &name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 4] = [
+        "Darksteel Ore",
+        "Darksteel Nugget",
+        "Mythril Ore",
+        "Grade 3 Tincture of Strength",
+    ];
+
+    fn best_match(query: &str) -> &'static str {
+        NAMES
+            .into_iter()
+            .max_by(|a, b| {
+                get_name_match_score(query, a).total_cmp(&get_name_match_score(query, b))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn scores_exact_names_highest() {
+        assert_eq!(get_name_match_score(" darksteel ORE ", "Darksteel Ore"), 1.);
+        assert_eq!(best_match("mythril ore"), "Mythril Ore");
+    }
+
+    #[test]
+    fn scores_contained_queries_above_typos() {
+        let prefix_score = get_name_match_score("darksteel", "Darksteel Nugget");
+
+        assert!(prefix_score > 0.9 && prefix_score < 1.);
+        assert!(prefix_score > get_name_match_score("darksteal", "Darksteel Nugget"));
+    }
+
+    #[test]
+    fn tolerates_one_typo() {
+        assert_eq!(best_match("darksteal ore"), "Darksteel Ore");
+        assert_eq!(best_match("mythrl ore"), "Mythril Ore");
+        assert!(get_name_match_score("darksteal ore", "Darksteel Ore") > 0.8);
+    }
+
+    #[test]
+    fn scores_unrelated_names_low() {
+        assert!(get_name_match_score("tincture", "Darksteel Ore") < 0.7);
+        assert_eq!(
+            best_match("tincture strength"),
+            "Grade 3 Tincture of Strength"
+        );
+        assert_eq!(get_name_match_score("  ", "Darksteel Ore"), 0.);
+    }
+}
@@ -0,0 +1,51 @@
+use universalis_sdk::ItemSaleHistoryUnit;
+
+#[derive(Debug, Copy, Clone)]
+pub struct PriceStats {
+    pub avg_price: f64,
+    pub std_dev: f64,
+    /// Standard deviation relative to the average price
+    pub cv: f64,
+    pub sample_count: usize,
+}
+
+/// Quantity weighted price statistics, `None` when nothing was sold
+pub fn get_price_stats(sales: &[ItemSaleHistoryUnit]) -> Option<PriceStats> {
+    let mut total_gil_spent: f64 = 0.;
+    let mut quantity: f64 = 0.;
+
+    for sale in sales {
+        total_gil_spent += sale.price_per_unit as f64 * sale.quantity as f64;
+        quantity += sale.quantity as f64;
+    }
+
+    if quantity == 0. {
+        return None;
+    }
+
+    let avg_price = total_gil_spent / quantity;
+
+    let variance = sales
+        .iter()
+        .map(|sale| sale.quantity as f64 * (sale.price_per_unit as f64 - avg_price).powi(2))
+        .sum::<f64>()
+        / quantity;
+    let std_dev = variance.sqrt();
+
+    Some(PriceStats {
+        avg_price,
+        std_dev,
+        cv: if avg_price > 0. {
+            std_dev / avg_price
+        } else {
+            0.
+        },
+        sample_count: sales.len(),
+    })
+}
+
+/// Score in `[0, 1)` growing with the number of sales and shrinking with price volatility. It
+/// reaches ~63% of its maximum at `confidence_sample_count` sales
+pub fn get_confidence_score(sample_count: usize, cv: f64, confidence_sample_count: f64) -> f64 {
+    (1. - (-(sample_count as f64) / confidence_sample_count.max(1.)).exp()) / (1. + cv)
+}
@@ -0,0 +1,134 @@
+use super::SECONDS_IN_DAY;
+use universalis_sdk::ItemSaleHistoryUnit;
+
+const SECONDS_IN_HOUR: i64 = 60 * 60;
+/// 1970-01-01 was a Thursday, weekdays are counted from Monday
+const EPOCH_WEEKDAY: i64 = 3;
+
+pub const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+#[derive(Debug, Copy, Clone)]
+pub struct SaleTiming {
+    /// Share of sold units per hour of day, from 0 to 1
+    pub by_hour: [f64; 24],
+    /// Share of sold units per day of week starting on Monday, from 0 to 1
+    pub by_weekday: [f64; 7],
+}
+
+impl SaleTiming {
+    pub fn peak_hour(&self) -> usize {
+        peak_index(&self.by_hour)
+    }
+
+    pub fn peak_weekday(&self) -> usize {
+        peak_index(&self.by_weekday)
+    }
+}
+
+/// Quantity weighted distribution of sales over hours of day and days of week,
+/// shifted by `utc_offset_hours`. `None` when nothing was sold
+pub fn get_sale_timing(sales: &[ItemSaleHistoryUnit], utc_offset_hours: i64) -> Option<SaleTiming> {
+    let mut by_hour = [0.; 24];
+    let mut by_weekday = [0.; 7];
+    let mut quantity: f64 = 0.;
+
+    for sale in sales {
+        let timestamp = sale.timestamp as i64 + utc_offset_hours * SECONDS_IN_HOUR;
+        let hour = timestamp.rem_euclid(SECONDS_IN_DAY as i64) / SECONDS_IN_HOUR;
+        let weekday = (timestamp.div_euclid(SECONDS_IN_DAY as i64) + EPOCH_WEEKDAY).rem_euclid(7);
+
+        by_hour[hour as usize] += sale.quantity as f64;
+        by_weekday[weekday as usize] += sale.quantity as f64;
+        quantity += sale.quantity as f64;
+    }
+
+    if quantity == 0. {
+        return None;
+    }
+
+    by_hour.iter_mut().for_each(|share| *share /= quantity);
+    by_weekday.iter_mut().for_each(|share| *share /= quantity);
+
+    Some(SaleTiming {
+        by_hour,
+        by_weekday,
+    })
+}
+
+fn peak_index(shares: &[f64]) -> usize {
+    shares
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-01 00:00 UTC, a Monday
+    const MONDAY: u64 = 1_704_067_200;
+
+    fn sale(timestamp: u64, quantity: u64) -> ItemSaleHistoryUnit {
+        ItemSaleHistoryUnit {
+            quantity,
+            price_per_unit: 100,
+            hq: false,
+            timestamp,
+            buyer_name: None,
+        }
+    }
+
+    #[test]
+    fn counts_weekdays_from_monday() {
+        let timing = get_sale_timing(&[sale(0, 1)], 0).unwrap();
+        assert_eq!(WEEKDAYS[timing.peak_weekday()], "Thursday");
+
+        let timing = get_sale_timing(&[sale(MONDAY, 1)], 0).unwrap();
+        assert_eq!(WEEKDAYS[timing.peak_weekday()], "Monday");
+    }
+
+    #[test]
+    fn weights_sales_by_quantity() {
+        let sales = [sale(MONDAY + 15 * 3600, 3), sale(MONDAY - 1800, 1)];
+
+        let timing = get_sale_timing(&sales, 0).unwrap();
+
+        assert_eq!(timing.by_weekday[0], 0.75);
+        assert_eq!(timing.by_weekday[6], 0.25);
+        assert_eq!(timing.by_hour[15], 0.75);
+        assert_eq!(timing.by_hour[23], 0.25);
+        assert_eq!(timing.peak_hour(), 15);
+    }
+
+    #[test]
+    fn shifts_by_the_utc_offset() {
+        let sales = [sale(MONDAY + 15 * 3600, 3), sale(MONDAY - 1800, 1)];
+
+        let timing = get_sale_timing(&sales, 1).unwrap();
+        assert_eq!(timing.by_weekday[0], 1.);
+        assert_eq!(timing.by_hour[0], 0.25);
+        assert_eq!(timing.by_hour[16], 0.75);
+
+        // Before the epoch in local time
+        let timing = get_sale_timing(&[sale(0, 1)], -1).unwrap();
+        assert_eq!(WEEKDAYS[timing.peak_weekday()], "Wednesday");
+        assert_eq!(timing.peak_hour(), 23);
+    }
+
+    #[test]
+    fn needs_sold_units() {
+        assert!(get_sale_timing(&[], 0).is_none());
+        assert!(get_sale_timing(&[sale(MONDAY, 0)], 0).is_none());
+    }
+}
@@ -0,0 +1,120 @@
+const DAYS_IN_WEEK: f64 = 7.;
+
+#[derive(Debug, Copy, Clone)]
+pub struct PricePoint {
+    /// Days since an arbitrary origin, only differences between points matter
+    pub day: f64,
+    pub price: f64,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PriceTrend {
+    pub latest_price: f64,
+    /// Least squares slope in gil per day
+    pub slope: f64,
+    /// Average price of the last 7 days compared to the 7 days before, in percents
+    pub week_over_week_change: Option<f64>,
+}
+
+pub fn get_price_trend(points: &[PricePoint]) -> Option<PriceTrend> {
+    let latest = points.iter().max_by(|a, b| a.day.total_cmp(&b.day))?;
+
+    if points.len() < 2 {
+        return None;
+    }
+
+    let count = points.len() as f64;
+    let mean_day = points.iter().map(|point| point.day).sum::<f64>() / count;
+    let mean_price = points.iter().map(|point| point.price).sum::<f64>() / count;
+
+    let covariance = points
+        .iter()
+        .map(|point| (point.day - mean_day) * (point.price - mean_price))
+        .sum::<f64>();
+    let day_variance = points
+        .iter()
+        .map(|point| (point.day - mean_day).powi(2))
+        .sum::<f64>();
+
+    if day_variance == 0. {
+        return None;
+    }
+
+    let this_week = average_price_between(points, latest.day - DAYS_IN_WEEK, latest.day);
+    let last_week = average_price_between(
+        points,
+        latest.day - 2. * DAYS_IN_WEEK,
+        latest.day - DAYS_IN_WEEK,
+    );
+
+    let week_over_week_change = match (this_week, last_week) {
+        (Some(this_week), Some(last_week)) if last_week > 0. => {
+            Some((this_week - last_week) / last_week * 100.)
+        }
+        _ => None,
+    };
+
+    Some(PriceTrend {
+        latest_price: latest.price,
+        slope: covariance / day_variance,
+        week_over_week_change,
+    })
+}
+
+/// Average price of points in the `(from, to]` day range
+fn average_price_between(points: &[PricePoint], from: f64, to: f64) -> Option<f64> {
+    let prices = points
+        .iter()
+        .filter(|point| point.day > from && point.day <= to)
+        .map(|point| point.price)
+        .collect::<Vec<_>>();
+
+    if prices.is_empty() {
+        return None;
+    }
+
+    Some(prices.iter().sum::<f64>() / prices.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(prices: &[(f64, f64)]) -> Vec<PricePoint> {
+        prices
+            .iter()
+            .map(|&(day, price)| PricePoint { day, price })
+            .collect()
+    }
+
+    #[test]
+    fn fits_a_linear_trend() {
+        let points = points(
+            &(0..14)
+                .map(|day| (day as f64, 100. + 10. * day as f64))
+                .collect::<Vec<_>>(),
+        );
+
+        let trend = get_price_trend(&points).unwrap();
+
+        assert_eq!(trend.latest_price, 230.);
+        assert!((trend.slope - 10.).abs() < 1e-9);
+        // Days 7-13 average 200 gil, days 0-6 average 130 gil
+        assert!((trend.week_over_week_change.unwrap() - 70. / 130. * 100.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn needs_a_previous_week_for_the_change() {
+        let trend = get_price_trend(&points(&[(10., 100.), (12., 80.)])).unwrap();
+
+        assert!((trend.slope + 10.).abs() < 1e-9);
+        assert_eq!(trend.week_over_week_change, None);
+    }
+
+    #[test]
+    fn needs_points_on_two_days() {
+        assert!(get_price_trend(&[]).is_none());
+        assert!(get_price_trend(&points(&[(1., 100.)])).is_none());
+        assert!(get_price_trend(&points(&[(1., 100.), (1., 200.)])).is_none());
+    }
+}
@@ -1,12 +1,12 @@
-use futures::stream::BoxStream;
-use futures::StreamExt;
-use sqlx::{Error, MySql, Pool, QueryBuilder, Row};
-use std::fmt::format;
+use serde::Serialize;
+use sqlx::types::time::PrimitiveDateTime;
+use sqlx::{Error, FromRow, MySql, Pool, QueryBuilder, Row};
 use universalis_sdk::xivapi::Item;
-use universalis_sdk::{ItemTradeVolume, Server};
+use universalis_sdk::{ItemTradeVolume, Server, TaxRates};
 
 const BIND_LIMIT: usize = 65535;
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct DBDataCenter {
     pub id: u64,
@@ -14,11 +14,24 @@ pub struct DBDataCenter {
     pub region: String,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct DBWorld {
     pub world_id: u64,
     pub name: String,
     pub data_center_id: u64,
+    /// `open`, `congested` or `restricted`
+    pub travel_status: String,
+}
+
+impl DBWorld {
+    pub fn is_congested(&self) -> bool {
+        self.travel_status == "congested"
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.travel_status == "restricted"
+    }
 }
 
 pub struct DBServer {
@@ -26,21 +39,118 @@ pub struct DBServer {
     pub worlds: Vec<DBWorld>,
 }
 
-#[derive(Debug, Clone)]
+#[allow(dead_code)]
+#[derive(Debug, Clone, FromRow)]
 pub struct DBItem {
     pub item_id: u64,
     pub name: String,
+    pub search_category_id: Option<u64>,
+    pub ui_category_id: Option<u64>,
+    pub level_item: u64,
+    pub stack_size: u64,
 }
 
 #[derive(Debug, Copy, Clone)]
-pub struct DBItemTradeVolume {
+pub struct WorldPrice {
+    pub item_id: u64,
+    pub world_id: u64,
+    pub avg_price: f64,
+    pub sample_count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DBSyncRunWorldPrice {
+    pub item_id: u64,
+    pub world_id: u64,
+    pub avg_price: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DBSyncRun {
     pub id: u64,
+    pub world_id: u64,
+    pub status: String,
+    pub items_count: u64,
+    pub rejected_items_count: u64,
+    pub started_at: PrimitiveDateTime,
+    pub finished_at: Option<PrimitiveDateTime>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct DBTaxRate {
+    pub world_id: u64,
+    pub city: String,
+    pub rate: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DBWorldPrice {
     pub item_id: u64,
+    pub item_name: String,
+    pub avg_price: f64,
+    pub recorded_at: PrimitiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct DBItemWorldPrice {
     pub world_id: u64,
+    pub avg_price: f64,
+    pub recorded_at: PrimitiveDateTime,
+}
+
+/// Trade volume of the latest finished sync run joined with item and cheapest world data
+#[derive(Debug, Clone, Serialize)]
+pub struct DBTradeOpportunity {
+    pub item_id: u64,
+    pub item_name: String,
+    pub search_category_id: Option<u64>,
+    pub ui_category_id: Option<u64>,
+    pub stack_size: u64,
+    pub home_world_id: u64,
+    pub home_world_name: String,
     pub cheapest_world_id: u64,
-    pub sale_score: f64,
-    pub price_diff_score: f64,
+    pub cheapest_world_name: String,
     pub home_world_avg_price: f64,
+    pub cheapest_world_avg_price: f64,
+    pub price_diff_score: f64,
+    pub unit_profit: f64,
+    pub sale_score: f64,
+    pub forecast_velocity: f64,
+    pub expected_profit: f64,
+    pub competing_retainers: u64,
+    pub days_of_supply: f64,
+    pub lowest_listing_changes: u64,
+    pub saturation_score: f64,
+    pub price_std_dev: f64,
+    pub price_cv: f64,
+    pub sample_count: u64,
+    pub confidence_score: f64,
+    pub manipulation_flags: String,
+    pub data_age: u64,
+    pub is_stale: bool,
+}
+
+impl DBTradeOpportunity {
+    /// Cheapest world price including the market tax paid by the buyer
+    pub fn unit_cost(&self) -> f64 {
+        self.home_world_avg_price - self.unit_profit
+    }
+
+    pub fn is_suspicious(&self) -> bool {
+        !self.manipulation_flags.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ItemFilter {
+    pub search_category_ids: Vec<u64>,
+    pub ui_category_ids: Vec<u64>,
+    pub min_item_level: Option<u64>,
+    pub max_item_level: Option<u64>,
+    pub name_pattern: Option<String>,
+    pub item_ids: Vec<u64>,
+    pub watchlist: Option<String>,
 }
 
 pub struct ItemData {
@@ -75,16 +185,28 @@ impl ItemData {
     }
 
     pub async fn save_items(&self, items: Vec<Item>) -> Result<(), Error> {
-        let mut query_builder: QueryBuilder<MySql> =
-            QueryBuilder::new("INSERT IGNORE INTO items(item_id, name)");
-
-        let items_chunks = items.chunks(BIND_LIMIT / 2);
+        let items_chunks = items.chunks(BIND_LIMIT / 6);
 
         for items_chunk in items_chunks {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new(
+                "INSERT INTO items(item_id, name, search_category_id, ui_category_id, level_item, stack_size)",
+            );
+
             query_builder.push_values(items_chunk, |mut b, item| {
-                b.push_bind(item.id).push_bind(&item.name);
+                b.push_bind(item.id)
+                    .push_bind(&item.name)
+                    .push_bind(item.search_category_id)
+                    .push_bind(item.ui_category_id)
+                    .push_bind(item.level_item)
+                    .push_bind(item.stack_size);
             });
 
+            query_builder.push(
+                " ON DUPLICATE KEY UPDATE name = VALUES(name), search_category_id = VALUES(search_category_id), \
+                ui_category_id = VALUES(ui_category_id), level_item = VALUES(level_item), \
+                stack_size = VALUES(stack_size)",
+            );
+
             query_builder.build().execute(&self.pool).await?;
         }
 
@@ -92,6 +214,10 @@ impl ItemData {
     }
 
     pub async fn delete_items(&self, items_ids: Vec<u64>) -> Result<(), Error> {
+        if items_ids.is_empty() {
+            return Ok(());
+        }
+
         let query = format!(
             "DELETE FROM items WHERE item_id IN ({})",
             items_ids
@@ -106,10 +232,143 @@ impl ItemData {
         Ok(())
     }
 
-    pub fn get_items(&self) -> BoxStream<Result<DBItem, Error>> {
-        sqlx::query_as!(DBItem, r"SELECT * FROM items")
-            .fetch(&self.pool)
-            .boxed()
+    pub async fn get_all_items(&self) -> Result<Vec<DBItem>, Error> {
+        let items = sqlx::query_as!(DBItem, "SELECT * FROM items")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(items)
+    }
+
+    /// Finds an item by its id or exact name
+    pub async fn get_item(&self, item: &str) -> Result<DBItem, Error> {
+        if let Ok(item_id) = item.parse::<u64>() {
+            return sqlx::query_as!(DBItem, "SELECT * FROM items WHERE item_id = ?", item_id)
+                .fetch_one(&self.pool)
+                .await;
+        }
+
+        sqlx::query_as!(DBItem, "SELECT * FROM items WHERE name = ?", item)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn get_items(&self, filter: &ItemFilter) -> Result<Vec<DBItem>, Error> {
+        let mut query_builder: QueryBuilder<MySql> =
+            QueryBuilder::new("SELECT * FROM items WHERE 1 = 1");
+
+        if !filter.search_category_ids.is_empty() {
+            query_builder.push(" AND search_category_id IN (");
+            let mut separated = query_builder.separated(", ");
+            for id in filter.search_category_ids.iter() {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+        }
+
+        if !filter.ui_category_ids.is_empty() {
+            query_builder.push(" AND ui_category_id IN (");
+            let mut separated = query_builder.separated(", ");
+            for id in filter.ui_category_ids.iter() {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+        }
+
+        if let Some(min_item_level) = filter.min_item_level {
+            query_builder
+                .push(" AND level_item >= ")
+                .push_bind(min_item_level);
+        }
+
+        if let Some(max_item_level) = filter.max_item_level {
+            query_builder
+                .push(" AND level_item <= ")
+                .push_bind(max_item_level);
+        }
+
+        if let Some(name_pattern) = &filter.name_pattern {
+            query_builder
+                .push(" AND name REGEXP ")
+                .push_bind(name_pattern);
+        }
+
+        if !filter.item_ids.is_empty() {
+            query_builder.push(" AND item_id IN (");
+            let mut separated = query_builder.separated(", ");
+            for id in filter.item_ids.iter() {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+        }
+
+        if let Some(watchlist) = &filter.watchlist {
+            query_builder
+                .push(" AND item_id IN (SELECT item_id FROM watchlist_items WHERE name = ")
+                .push_bind(watchlist)
+                .push(")");
+        }
+
+        let items = query_builder
+            .build_query_as::<DBItem>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(items)
+    }
+}
+
+pub struct WatchlistData {
+    pool: Pool<MySql>,
+}
+
+impl WatchlistData {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn add_items(&self, watchlist: String, items_ids: Vec<u64>) -> Result<(), Error> {
+        let mut query_builder: QueryBuilder<MySql> =
+            QueryBuilder::new("INSERT IGNORE INTO watchlist_items (name, item_id)");
+
+        query_builder.push_values(items_ids, |mut b, item_id| {
+            b.push_bind(&watchlist).push_bind(item_id);
+        });
+
+        query_builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_items(&self, watchlist: String, items_ids: Vec<u64>) -> Result<(), Error> {
+        let mut query_builder: QueryBuilder<MySql> =
+            QueryBuilder::new("DELETE FROM watchlist_items WHERE name = ");
+
+        query_builder.push_bind(watchlist).push(" AND item_id IN (");
+
+        let mut separated = query_builder.separated(", ");
+
+        for id in items_ids.iter() {
+            separated.push_bind(id);
+        }
+
+        separated.push_unseparated(")");
+
+        query_builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_items(&self, watchlist: String) -> Result<Vec<DBItem>, Error> {
+        let items = sqlx::query_as!(
+            DBItem,
+            "SELECT items.* FROM items JOIN watchlist_items ON watchlist_items.item_id = items.item_id WHERE watchlist_items.name = ?",
+            watchlist
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
     }
 }
 
@@ -124,22 +383,39 @@ impl ItemTrades {
 
     pub async fn save_item_trade_volumes(
         &self,
+        sync_run_id: u64,
         items_trade_volumes: Vec<ItemTradeVolume>,
     ) -> Result<(), Error> {
-        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new(
-            "INSERT IGNORE INTO items_trade_volumes (item_id, world_id, sale_score, price_diff_score, cheapest_world_id, home_world_avg_price)"
-        );
-
-        let items_trade_volumes_chunks = items_trade_volumes.chunks(BIND_LIMIT / 6);
+        let items_trade_volumes_chunks = items_trade_volumes.chunks(BIND_LIMIT / 22);
 
         for items_trade_volumes_chunk in items_trade_volumes_chunks {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new(
+                "INSERT IGNORE INTO items_trade_volumes (item_id, world_id, sale_score, price_diff_score, cheapest_world_id, home_world_avg_price, cheapest_world_avg_price, expected_profit, competing_retainers, days_of_supply, lowest_listing_changes, saturation_score, price_std_dev, price_cv, sample_count, confidence_score, unit_profit, forecast_velocity, manipulation_flags, data_age, is_stale, sync_run_id)"
+            );
+
             query_builder.push_values(items_trade_volumes_chunk, |mut b, item_trade_volume| {
                 b.push_bind(item_trade_volume.item_id)
                     .push_bind(item_trade_volume.world_id)
                     .push_bind(item_trade_volume.sale_score)
                     .push_bind(item_trade_volume.price_diff_score)
                     .push_bind(item_trade_volume.cheapest_world_id)
-                    .push_bind(item_trade_volume.home_world_avg_price);
+                    .push_bind(item_trade_volume.home_world_avg_price)
+                    .push_bind(item_trade_volume.cheapest_world_avg_price)
+                    .push_bind(item_trade_volume.expected_profit)
+                    .push_bind(item_trade_volume.competing_retainers)
+                    .push_bind(item_trade_volume.days_of_supply)
+                    .push_bind(item_trade_volume.lowest_listing_changes)
+                    .push_bind(item_trade_volume.saturation_score)
+                    .push_bind(item_trade_volume.price_std_dev)
+                    .push_bind(item_trade_volume.price_cv)
+                    .push_bind(item_trade_volume.sample_count)
+                    .push_bind(item_trade_volume.confidence_score)
+                    .push_bind(item_trade_volume.unit_profit)
+                    .push_bind(item_trade_volume.forecast_velocity)
+                    .push_bind(item_trade_volume.manipulation_flags)
+                    .push_bind(item_trade_volume.data_age)
+                    .push_bind(item_trade_volume.is_stale)
+                    .push_bind(sync_run_id);
             });
 
             query_builder.build().execute(&self.pool).await?;
@@ -147,6 +423,85 @@ impl ItemTrades {
 
         Ok(())
     }
+
+    /// Trade opportunities found by the latest finished sync run of the home world
+    pub async fn get_trade_opportunities(
+        &self,
+        world_id: u64,
+    ) -> Result<Vec<DBTradeOpportunity>, Error> {
+        sqlx::query_as!(
+            DBTradeOpportunity,
+            "SELECT items_trade_volumes.item_id, items.name AS item_name, items.search_category_id, \
+            items.ui_category_id, items.stack_size, \
+            items_trade_volumes.world_id AS home_world_id, home_worlds.name AS home_world_name, \
+            items_trade_volumes.cheapest_world_id, worlds.name AS cheapest_world_name, \
+            items_trade_volumes.home_world_avg_price, items_trade_volumes.cheapest_world_avg_price, \
+            items_trade_volumes.price_diff_score, items_trade_volumes.unit_profit, \
+            items_trade_volumes.sale_score, items_trade_volumes.forecast_velocity, \
+            items_trade_volumes.expected_profit, items_trade_volumes.competing_retainers, \
+            items_trade_volumes.days_of_supply, items_trade_volumes.lowest_listing_changes, \
+            items_trade_volumes.saturation_score, items_trade_volumes.price_std_dev, \
+            items_trade_volumes.price_cv, items_trade_volumes.sample_count, \
+            items_trade_volumes.confidence_score, items_trade_volumes.manipulation_flags, \
+            items_trade_volumes.data_age, items_trade_volumes.is_stale AS `is_stale: bool` \
+            FROM items_trade_volumes \
+            JOIN items ON items.item_id = items_trade_volumes.item_id \
+            JOIN worlds ON worlds.world_id = items_trade_volumes.cheapest_world_id \
+            JOIN worlds AS home_worlds ON home_worlds.world_id = items_trade_volumes.world_id \
+            WHERE items_trade_volumes.sync_run_id = (SELECT MAX(id) FROM sync_runs WHERE world_id = ? AND status = 'finished')",
+            world_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Trade opportunities found by the sync run
+    pub async fn get_sync_run_trade_opportunities(
+        &self,
+        sync_run_id: u64,
+    ) -> Result<Vec<DBTradeOpportunity>, Error> {
+        sqlx::query_as!(
+            DBTradeOpportunity,
+            "SELECT items_trade_volumes.item_id, items.name AS item_name, items.search_category_id, \
+            items.ui_category_id, items.stack_size, \
+            items_trade_volumes.world_id AS home_world_id, home_worlds.name AS home_world_name, \
+            items_trade_volumes.cheapest_world_id, worlds.name AS cheapest_world_name, \
+            items_trade_volumes.home_world_avg_price, items_trade_volumes.cheapest_world_avg_price, \
+            items_trade_volumes.price_diff_score, items_trade_volumes.unit_profit, \
+            items_trade_volumes.sale_score, items_trade_volumes.forecast_velocity, \
+            items_trade_volumes.expected_profit, items_trade_volumes.competing_retainers, \
+            items_trade_volumes.days_of_supply, items_trade_volumes.lowest_listing_changes, \
+            items_trade_volumes.saturation_score, items_trade_volumes.price_std_dev, \
+            items_trade_volumes.price_cv, items_trade_volumes.sample_count, \
+            items_trade_volumes.confidence_score, items_trade_volumes.manipulation_flags, \
+            items_trade_volumes.data_age, items_trade_volumes.is_stale AS `is_stale: bool` \
+            FROM items_trade_volumes \
+            JOIN items ON items.item_id = items_trade_volumes.item_id \
+            JOIN worlds ON worlds.world_id = items_trade_volumes.cheapest_world_id \
+            JOIN worlds AS home_worlds ON home_worlds.world_id = items_trade_volumes.world_id \
+            WHERE items_trade_volumes.sync_run_id = ?",
+            sync_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Items flagged for market manipulation by the latest finished sync run of the world
+    pub async fn get_suspicious_items_ids(&self, world_id: u64) -> Result<Vec<u64>, Error> {
+        let suspicious_items = sqlx::query!(
+            "SELECT item_id FROM items_trade_volumes \
+            WHERE manipulation_flags <> '' \
+            AND sync_run_id = (SELECT MAX(id) FROM sync_runs WHERE world_id = ? AND status = 'finished')",
+            world_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(suspicious_items
+            .into_iter()
+            .map(|suspicious_item| suspicious_item.item_id)
+            .collect())
+    }
 }
 
 pub struct ServerData {
@@ -169,12 +524,7 @@ impl ServerData {
             .await?
             .last_insert_id();
             for world in server.worlds {
-                if server
-                    .data_center
-                    .worlds
-                    .iter()
-                    .any(|&dc_world_id| dc_world_id == world.id)
-                {
+                if server.data_center.worlds.contains(&world.id) {
                     sqlx::query!(
                     "INSERT IGNORE INTO worlds (world_id, name, data_center_id) VALUES (?, ?, ?)",
                     world.id,
@@ -213,6 +563,62 @@ impl ServerData {
         })
     }
 
+    pub async fn get_region_data_centers(
+        &self,
+        region: String,
+    ) -> Result<Vec<DBDataCenter>, Error> {
+        let data_centers = sqlx::query_as!(
+            DBDataCenter,
+            "SELECT * FROM data_centers WHERE region = ?",
+            region
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(data_centers)
+    }
+
+    pub async fn get_world_by_name(&self, world_name: String) -> Result<DBWorld, Error> {
+        let world = sqlx::query_as!(DBWorld, "SELECT * FROM worlds WHERE name = ?", world_name)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(world)
+    }
+
+    pub async fn get_data_center_worlds(&self, data_center_id: u64) -> Result<Vec<DBWorld>, Error> {
+        let worlds = sqlx::query_as!(
+            DBWorld,
+            "SELECT * FROM worlds WHERE data_center_id = ?",
+            data_center_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(worlds)
+    }
+
+    pub async fn set_travel_status(&self, world_name: &str, status: &str) -> Result<u64, Error> {
+        let updated = sqlx::query!(
+            "UPDATE worlds SET travel_status = ? WHERE name = ?",
+            status,
+            world_name
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(updated)
+    }
+
+    pub async fn reset_travel_statuses(&self) -> Result<(), Error> {
+        sqlx::query!("UPDATE worlds SET travel_status = 'open'")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn get_data_center_world_by_name(
         &self,
         world_name: String,
@@ -230,3 +636,244 @@ impl ServerData {
         Ok(world)
     }
 }
+
+pub struct SyncRunData {
+    pool: Pool<MySql>,
+}
+
+impl SyncRunData {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn start_sync_run(&self, data_center_id: u64, world_id: u64) -> Result<u64, Error> {
+        let sync_run_id = sqlx::query!(
+            "INSERT INTO sync_runs (data_center_id, world_id) VALUES (?, ?)",
+            data_center_id,
+            world_id
+        )
+        .execute(&self.pool)
+        .await?
+        .last_insert_id();
+
+        Ok(sync_run_id)
+    }
+
+    pub async fn finish_sync_run(
+        &self,
+        sync_run_id: u64,
+        items_count: u64,
+        rejected_items_count: u64,
+        recommended_retainer_city: &str,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE sync_runs SET status = 'finished', items_count = ?, rejected_items_count = ?, recommended_retainer_city = ?, finished_at = NOW() WHERE id = ?",
+            items_count,
+            rejected_items_count,
+            recommended_retainer_city,
+            sync_run_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_latest_finished_sync_run_id(
+        &self,
+        world_id: u64,
+    ) -> Result<Option<u64>, Error> {
+        let sync_run = sqlx::query!(
+            "SELECT MAX(id) AS id FROM sync_runs WHERE world_id = ? AND status = 'finished'",
+            world_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(sync_run.id)
+    }
+
+    /// Previous finished sync run of the world before `sync_run_id`
+    pub async fn get_previous_finished_sync_run_id(
+        &self,
+        world_id: u64,
+        sync_run_id: u64,
+    ) -> Result<Option<u64>, Error> {
+        let sync_run = sqlx::query!(
+            "SELECT MAX(id) AS id FROM sync_runs WHERE world_id = ? AND status = 'finished' AND id < ?",
+            world_id,
+            sync_run_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(sync_run.id)
+    }
+
+    pub async fn get_sync_run(&self, sync_run_id: u64) -> Result<DBSyncRun, Error> {
+        sqlx::query_as!(
+            DBSyncRun,
+            "SELECT id, world_id, status, items_count, rejected_items_count, started_at, finished_at FROM sync_runs WHERE id = ?",
+            sync_run_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Latest sync runs of the world, the newest first
+    pub async fn get_sync_runs(&self, world_id: u64, limit: u64) -> Result<Vec<DBSyncRun>, Error> {
+        sqlx::query_as!(
+            DBSyncRun,
+            "SELECT id, world_id, status, items_count, rejected_items_count, started_at, finished_at FROM sync_runs WHERE world_id = ? ORDER BY id DESC LIMIT ?",
+            world_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn fail_sync_run(&self, sync_run_id: u64) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE sync_runs SET status = 'failed', finished_at = NOW() WHERE id = ?",
+            sync_run_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub struct TaxRateData {
+    pool: Pool<MySql>,
+}
+
+impl TaxRateData {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn save_tax_rates(
+        &self,
+        sync_run_id: u64,
+        world_id: u64,
+        tax_rates: &TaxRates,
+    ) -> Result<(), Error> {
+        let mut query_builder: QueryBuilder<MySql> =
+            QueryBuilder::new("INSERT INTO tax_rates (sync_run_id, world_id, city, rate)");
+
+        query_builder.push_values(tax_rates.get_cities_rates(), |mut b, (city, rate)| {
+            b.push_bind(sync_run_id)
+                .push_bind(world_id)
+                .push_bind(city)
+                .push_bind(rate);
+        });
+
+        query_builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_tax_rates(&self, sync_run_id: u64) -> Result<Vec<DBTaxRate>, Error> {
+        sqlx::query_as!(
+            DBTaxRate,
+            "SELECT world_id, city, rate FROM tax_rates WHERE sync_run_id = ?",
+            sync_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+pub struct WorldPriceData {
+    pool: Pool<MySql>,
+}
+
+impl WorldPriceData {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn save_world_prices(
+        &self,
+        sync_run_id: u64,
+        world_prices: Vec<WorldPrice>,
+    ) -> Result<(), Error> {
+        for world_prices_chunk in world_prices.chunks(BIND_LIMIT / 5) {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new(
+                "INSERT INTO world_prices (sync_run_id, item_id, world_id, avg_price, sample_count)",
+            );
+
+            query_builder.push_values(world_prices_chunk, |mut b, world_price| {
+                b.push_bind(sync_run_id)
+                    .push_bind(world_price.item_id)
+                    .push_bind(world_price.world_id)
+                    .push_bind(world_price.avg_price)
+                    .push_bind(world_price.sample_count);
+            });
+
+            query_builder.build().execute(&self.pool).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Average prices of every data center world recorded by the sync run
+    pub async fn get_world_prices(
+        &self,
+        sync_run_id: u64,
+    ) -> Result<Vec<DBSyncRunWorldPrice>, Error> {
+        sqlx::query_as!(
+            DBSyncRunWorldPrice,
+            "SELECT item_id, world_id, avg_price FROM world_prices WHERE sync_run_id = ?",
+            sync_run_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Average prices recorded on the world by finished sync runs during the last `days`
+    pub async fn get_world_price_history(
+        &self,
+        world_id: u64,
+        days: u32,
+    ) -> Result<Vec<DBWorldPrice>, Error> {
+        let world_prices = sqlx::query_as!(
+            DBWorldPrice,
+            "SELECT world_prices.item_id, items.name AS item_name, world_prices.avg_price, sync_runs.started_at AS recorded_at \
+            FROM world_prices \
+            JOIN sync_runs ON sync_runs.id = world_prices.sync_run_id \
+            JOIN items ON items.item_id = world_prices.item_id \
+            WHERE world_prices.world_id = ? AND sync_runs.status = 'finished' AND sync_runs.started_at >= NOW() - INTERVAL ? DAY \
+            ORDER BY sync_runs.started_at",
+            world_id,
+            days
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(world_prices)
+    }
+
+    /// Average prices of the item recorded per world by finished sync runs during the last `days`
+    pub async fn get_item_price_history(
+        &self,
+        item_id: u64,
+        days: u32,
+    ) -> Result<Vec<DBItemWorldPrice>, Error> {
+        let world_prices = sqlx::query_as!(
+            DBItemWorldPrice,
+            "SELECT world_prices.world_id, world_prices.avg_price, sync_runs.started_at AS recorded_at \
+            FROM world_prices \
+            JOIN sync_runs ON sync_runs.id = world_prices.sync_run_id \
+            WHERE world_prices.item_id = ? AND sync_runs.status = 'finished' AND sync_runs.started_at >= NOW() - INTERVAL ? DAY \
+            ORDER BY sync_runs.started_at",
+            item_id,
+            days
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(world_prices)
+    }
+}
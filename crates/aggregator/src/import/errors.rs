@@ -11,6 +11,4 @@ pub enum Error {
     TokioJoin(#[from] JoinError),
     #[error("Error while executing sql")]
     Sqlx(#[from] sqlx::Error),
-    #[error("Error while accessing HashMap element")]
-    Test,
 }
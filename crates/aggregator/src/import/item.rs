@@ -32,7 +32,7 @@ impl ItemImport {
             .get_items_ids_for_delete(marketable_items_ids)
             .await?;
 
-        self.item_data.delete_items(ids_for_delete);
+        self.item_data.delete_items(ids_for_delete).await?;
 
         Ok(())
     }
@@ -45,7 +45,7 @@ impl ItemImport {
             .get_items_ids_for_delete(marketable_items_ids)
             .await?;
 
-        self.item_data.delete_items(ids_for_delete);
+        self.item_data.delete_items(ids_for_delete).await?;
 
         Ok(())
     }
@@ -1,50 +1,234 @@
-use crate::db::{DBItem, DBWorld, ItemData, ItemTrades, ServerData};
+use crate::analysis::{
+    get_confidence_score, get_forecast_velocity, get_listing_saturation, get_manipulation_flags,
+    get_price_stats, get_purchase_price, now_timestamp, FORECAST_HISTORY_DAYS,
+};
+use crate::db::{
+    DBServer, DBWorld, ItemData, ItemFilter, ItemTrades, ServerData, SyncRunData, TaxRateData,
+    WorldPrice, WorldPriceData,
+};
 use crate::import::errors::Error;
 use futures::future::try_join_all;
-use futures::{TryFutureExt, TryStreamExt};
-use std::collections::hash_map::Entry::Vacant;
+use futures::{stream, StreamExt, TryStreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::collections::HashMap;
-use std::thread::sleep;
-use std::time::Duration;
+use tracing::{info, warn};
 use universalis_sdk::{
-    get_item_sale_history_by_world, get_item_velocity_by_world, ItemTradeVolume,
+    get_item_listings_by_world, get_item_sale_history_by_world, get_item_velocity_by_world,
+    get_tax_rates_by_world, ItemListingsMapping, ItemMapping, ItemTradeVolume, TaxRates,
 };
 
 pub struct MarketImport {
     item_trades: ItemTrades,
     server_data: ServerData,
     item_data: ItemData,
+    sync_run_data: SyncRunData,
+    tax_rate_data: TaxRateData,
+    world_price_data: WorldPriceData,
+    progress: MultiProgress,
+}
+
+/// Rows saved between progress updates
+const ROWS_PER_WRITE: usize = 1000;
+
+#[derive(Debug, Clone, Default)]
+pub struct TradeImportOptions {
+    pub item_filter: ItemFilter,
+    pub liquidity_thresholds: LiquidityThresholds,
+    /// Seconds after which Universalis data of a world is considered stale
+    pub max_data_age: u64,
+    pub exclude_stale: bool,
+    /// Percent added to congested worlds' prices when looking for the cheapest world
+    pub congested_world_penalty: f64,
+    /// Velocity requests in flight at the same time
+    pub max_concurrent_requests: usize,
+    /// Sales at which the confidence score reaches ~63% of its maximum
+    pub confidence_sample_count: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LiquidityThresholds {
+    pub min_velocity: f64,
+    pub min_sample_size: usize,
+    pub min_home_world_price: f64,
+    pub min_expected_profit: f64,
 }
 
+#[derive(Debug, Default)]
+pub struct RejectedItems {
+    /// Only sold on worlds that can't be traveled to
+    pub no_eligible_sales: usize,
+    pub no_home_world_sales: usize,
+    pub low_velocity: usize,
+    pub low_sample_size: usize,
+    pub low_home_world_price: usize,
+    pub low_expected_profit: usize,
+    pub stale_data: usize,
+}
+
+impl RejectedItems {
+    pub fn total(&self) -> usize {
+        self.no_eligible_sales
+            + self.no_home_world_sales
+            + self.low_velocity
+            + self.low_sample_size
+            + self.low_home_world_price
+            + self.low_expected_profit
+            + self.stale_data
+    }
+}
+
+pub struct SyncSummary {
+    pub sync_run_id: u64,
+    pub items_count: usize,
+    pub rejected_items: RejectedItems,
+    pub recommended_retainer_city: String,
+}
+
+#[derive(Default)]
 struct LowestAverageItemPrice {
     world_id: u64,
     price: f64,
+    price_cv: f64,
+    sample_size: usize,
+    /// Unix timestamp in milliseconds
+    last_upload_time: u64,
     home_world_price: f64,
+    home_world_price_std_dev: f64,
+    home_world_price_cv: f64,
+    home_world_sample_size: usize,
+    home_world_last_upload_time: u64,
+}
+
+impl LowestAverageItemPrice {
+    /// Seconds since the older of the cheapest world and home world data was uploaded, a world
+    /// without sales has no upload time and is left out
+    fn data_age(&self, now: u64) -> u64 {
+        let last_upload_time = [self.last_upload_time, self.home_world_last_upload_time]
+            .into_iter()
+            .filter(|&last_upload_time| last_upload_time > 0)
+            .min()
+            .unwrap_or_default()
+            / 1000;
+
+        now.saturating_sub(last_upload_time)
+    }
 }
 
 impl MarketImport {
-    pub fn new(item_trades: ItemTrades, server_data: ServerData, item_data: ItemData) -> Self {
+    pub fn new(
+        item_trades: ItemTrades,
+        server_data: ServerData,
+        item_data: ItemData,
+        sync_run_data: SyncRunData,
+        tax_rate_data: TaxRateData,
+        world_price_data: WorldPriceData,
+        progress: MultiProgress,
+    ) -> Self {
         Self {
             item_trades,
             server_data,
             item_data,
+            sync_run_data,
+            tax_rate_data,
+            world_price_data,
+            progress,
         }
     }
 
+    /// Bar of one sync step, prefixed with the data center so parallel syncs can be told apart
+    fn progress_bar(
+        &self,
+        data_center_name: &str,
+        message: &'static str,
+        len: usize,
+    ) -> ProgressBar {
+        let progress_bar = self.progress.add(ProgressBar::new(len as u64));
+        progress_bar.set_style(
+            ProgressStyle::with_template(
+                "{prefix:>10} {msg:<20} [{bar:30}] {pos}/{len} {elapsed_precise} ETA {eta}",
+            )
+            .unwrap()
+            .progress_chars("=> "),
+        );
+        progress_bar.set_prefix(data_center_name.to_string());
+        progress_bar.set_message(message);
+
+        progress_bar
+    }
+
     pub async fn import_market_trade_volumes(
         &self,
         data_center_name: String,
         home_world_name: String,
-    ) -> Result<(), Error> {
+        options: &TradeImportOptions,
+    ) -> Result<SyncSummary, Error> {
         let server = self.server_data.get_server(data_center_name).await?;
+        let home_world = self
+            .server_data
+            .get_data_center_world_by_name(home_world_name, server.data_center.id)
+            .await?;
 
-        let (home_world, items) = tokio::try_join!(
-            self.server_data
-                .get_data_center_world_by_name(home_world_name, server.data_center.id),
-            self.item_data.get_items().try_collect::<Vec<DBItem>>(),
-        )?;
+        let sync_run_id = self
+            .sync_run_data
+            .start_sync_run(server.data_center.id, home_world.world_id)
+            .await?;
+        info!(
+            data_center = %server.data_center.name,
+            home_world = %home_world.name,
+            sync_run_id,
+            "Sync run started"
+        );
+
+        match self
+            .import_sync_run(&server, &home_world, options, sync_run_id)
+            .await
+        {
+            Ok(summary) => {
+                self.sync_run_data
+                    .finish_sync_run(
+                        sync_run_id,
+                        summary.items_count as u64,
+                        summary.rejected_items.total() as u64,
+                        &summary.recommended_retainer_city,
+                    )
+                    .await?;
+
+                Ok(summary)
+            }
+            Err(error) => {
+                warn!(sync_run_id, ?error, "Sync run failed");
+                self.sync_run_data.fail_sync_run(sync_run_id).await?;
+
+                Err(error)
+            }
+        }
+    }
+
+    async fn import_sync_run(
+        &self,
+        server: &DBServer,
+        home_world: &DBWorld,
+        options: &TradeImportOptions,
+        sync_run_id: u64,
+    ) -> Result<SyncSummary, Error> {
+        let thresholds = &options.liquidity_thresholds;
+        let data_center_name = &server.data_center.name;
+        let items = self.item_data.get_items(&options.item_filter).await?;
+        info!(
+            data_center = %data_center_name,
+            items_count = items.len(),
+            worlds_count = server.worlds.len(),
+            "Fetching sale history"
+        );
+
+        let worlds_tax_rates = self.import_tax_rates(server, sync_run_id).await?;
+        let home_world_tax_rates = worlds_tax_rates
+            .get(&home_world.world_id)
+            .ok_or(Error::HashMapAccess)?;
+        let (recommended_retainer_city, _) = home_world_tax_rates.get_lowest_rate_city();
 
         let mut handles = HashMap::new();
+        let mut chunks_count = 0;
 
         for world in &server.worlds {
             let items_handles: Vec<_> = items
@@ -55,100 +239,449 @@ impl MarketImport {
                     tokio::spawn(get_item_sale_history_by_world(
                         chunk_ids,
                         world.name.clone(),
+                        None,
                     ))
                 })
                 .collect();
 
+            chunks_count += items_handles.len();
             handles.insert(&world.name, items_handles);
         }
 
         let mut lowest_avg_items_prices: HashMap<u64, LowestAverageItemPrice> = HashMap::new();
+        let mut world_prices = Vec::new();
+
+        // Restricted worlds can't be traveled to, congested worlds are only worth it when cheaper by the penalty
+        let price_factors: HashMap<u64, f64> = server
+            .worlds
+            .iter()
+            .filter(|world| world.world_id == home_world.world_id || !world.is_restricted())
+            .map(|world| {
+                let price_factor = if world.is_congested() {
+                    1. + options.congested_world_penalty / 100.
+                } else {
+                    1.
+                };
+
+                (world.world_id, price_factor)
+            })
+            .collect();
+
+        let sale_history_bar =
+            self.progress_bar(data_center_name, "sale history chunks", chunks_count);
 
         for world in &server.worlds {
             let sale_history_results = handles.get_mut(&world.name).ok_or(Error::HashMapAccess)?;
 
             for sale_history_unit in sale_history_results {
                 let sale_history = sale_history_unit.await??;
+                sale_history_bar.inc(1);
                 for (item_id, item_sale_history) in sale_history.items {
-                    let mut total_gil_spent: f64 = 0.;
-                    let mut quantity: f64 = 0.;
+                    let Some(price_stats) = get_price_stats(&item_sale_history.entries) else {
+                        continue;
+                    };
 
-                    for sale_history_unit in item_sale_history.entries {
-                        total_gil_spent += sale_history_unit.price_per_unit as f64
-                            * sale_history_unit.quantity as f64;
-                        quantity += sale_history_unit.quantity as f64;
-                    }
+                    world_prices.push(WorldPrice {
+                        item_id,
+                        world_id: world.world_id,
+                        avg_price: price_stats.avg_price,
+                        sample_count: price_stats.sample_count as u64,
+                    });
 
-                    if let Vacant(e) = lowest_avg_items_prices.entry(item_id) {
-                        let mut home_world_price = 0.0;
-                        if home_world.world_id == world.world_id {
-                            home_world_price = total_gil_spent / quantity;
-                        }
-                        e.insert(LowestAverageItemPrice {
-                            world_id: world.world_id,
-                            price: total_gil_spent / quantity,
-                            home_world_price,
-                        });
-                    } else {
-                        let lowest_avg_item_price = lowest_avg_items_prices
-                            .get_mut(&item_id)
-                            .ok_or(Error::HashMapAccess)?;
-
-                        let avg_item_price = total_gil_spent / quantity;
-
-                        if lowest_avg_item_price.price > avg_item_price {
-                            lowest_avg_item_price.price = avg_item_price;
-                            lowest_avg_item_price.world_id = world.world_id;
-                            if home_world.world_id == world.world_id {
-                                lowest_avg_item_price.home_world_price = total_gil_spent / quantity;
-                            }
+                    let lowest_avg_item_price = lowest_avg_items_prices.entry(item_id).or_default();
+
+                    let is_cheaper = match (
+                        price_factors.get(&world.world_id),
+                        price_factors.get(&lowest_avg_item_price.world_id),
+                    ) {
+                        (Some(price_factor), Some(lowest_price_factor)) => {
+                            lowest_avg_item_price.price * lowest_price_factor
+                                > price_stats.avg_price * price_factor
                         }
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    };
+
+                    if is_cheaper {
+                        lowest_avg_item_price.price = price_stats.avg_price;
+                        lowest_avg_item_price.price_cv = price_stats.cv;
+                        lowest_avg_item_price.world_id = world.world_id;
+                        lowest_avg_item_price.sample_size = price_stats.sample_count;
+                        lowest_avg_item_price.last_upload_time = item_sale_history.last_upload_time;
+                    }
+
+                    if home_world.world_id == world.world_id {
+                        lowest_avg_item_price.home_world_price = price_stats.avg_price;
+                        lowest_avg_item_price.home_world_price_std_dev = price_stats.std_dev;
+                        lowest_avg_item_price.home_world_price_cv = price_stats.cv;
+                        lowest_avg_item_price.home_world_sample_size = price_stats.sample_count;
+                        lowest_avg_item_price.home_world_last_upload_time =
+                            item_sale_history.last_upload_time;
                     }
                 }
             }
         }
 
+        sale_history_bar.finish_and_clear();
+
+        let world_prices_bar =
+            self.progress_bar(data_center_name, "world prices saved", world_prices.len());
+        for world_prices_chunk in world_prices.chunks(ROWS_PER_WRITE) {
+            self.world_price_data
+                .save_world_prices(sync_run_id, world_prices_chunk.to_vec())
+                .await?;
+            world_prices_bar.inc(world_prices_chunk.len() as u64);
+        }
+        world_prices_bar.finish_and_clear();
+        info!(
+            data_center = %data_center_name,
+            chunks_count,
+            world_prices_count = world_prices.len(),
+            "Sale history fetched"
+        );
+
+        let mut rejected_items = RejectedItems::default();
+        let now = now_timestamp();
+
         let trade_volumes_handlers = items
             .iter()
             .filter_map(|item| {
-                let lowest_avg_item_price = lowest_avg_items_prices.remove(&item.item_id);
+                let lowest_avg_item_price = lowest_avg_items_prices.remove(&item.item_id)?;
+
+                // Only sold on worlds that can't be traveled to
+                if lowest_avg_item_price.sample_size == 0 {
+                    rejected_items.no_eligible_sales += 1;
+                    return None;
+                }
+
+                // Without home world sales there is no resale price to profit from
+                if lowest_avg_item_price.home_world_sample_size == 0 {
+                    rejected_items.no_home_world_sales += 1;
+                    return None;
+                }
+
+                if lowest_avg_item_price
+                    .sample_size
+                    .min(lowest_avg_item_price.home_world_sample_size)
+                    < thresholds.min_sample_size
+                {
+                    rejected_items.low_sample_size += 1;
+                    return None;
+                }
+
+                if lowest_avg_item_price.home_world_price < thresholds.min_home_world_price {
+                    rejected_items.low_home_world_price += 1;
+                    return None;
+                }
 
-                if lowest_avg_item_price.is_none() {
+                let data_age = lowest_avg_item_price.data_age(now);
+                let is_stale = data_age > options.max_data_age;
+
+                if is_stale && options.exclude_stale {
+                    rejected_items.stale_data += 1;
                     return None;
                 }
 
+                let cities_rates = worlds_tax_rates
+                    .get(&lowest_avg_item_price.world_id)
+                    .map(|tax_rates| tax_rates.get_cities_rates())
+                    .unwrap_or_default();
+                let purchase_price = get_purchase_price(
+                    lowest_avg_item_price.price,
+                    cities_rates.into_iter().map(|(_, rate)| rate),
+                );
+
                 Some(MarketImport::avg_item_prices_to_trade_volume(
                     item.item_id,
                     home_world.clone(),
-                    lowest_avg_item_price.unwrap(),
+                    lowest_avg_item_price,
+                    purchase_price,
+                    data_age,
+                    is_stale,
+                    options.confidence_sample_count,
                 ))
             })
             .collect::<Vec<_>>();
 
-        let items_trade_volumes: Vec<ItemTradeVolume> =
-            try_join_all(trade_volumes_handlers).await?;
+        let velocity_bar = self.progress_bar(
+            data_center_name,
+            "velocity requests",
+            trade_volumes_handlers.len(),
+        );
+        let mut items_trade_volumes: Vec<ItemTradeVolume> = stream::iter(trade_volumes_handlers)
+            .map(|handler| async {
+                let item_trade_volume = handler.await;
+                velocity_bar.inc(1);
 
-        self.item_trades
-            .save_item_trade_volumes(items_trade_volumes)
+                item_trade_volume
+            })
+            .buffer_unordered(options.max_concurrent_requests.max(1))
+            .try_collect()
             .await?;
+        velocity_bar.finish_and_clear();
+        info!(
+            data_center = %data_center_name,
+            items_count = items_trade_volumes.len(),
+            "Velocity fetched"
+        );
+
+        let home_world_bar = self.progress_bar(
+            data_center_name,
+            "home world history",
+            items_trade_volumes.chunks(90).len(),
+        );
+        let items_sale_history = MarketImport::get_home_world_sale_history(
+            home_world,
+            &items_trade_volumes,
+            &home_world_bar,
+        )
+        .await?;
+        home_world_bar.finish_and_clear();
+        MarketImport::apply_demand_forecast(&items_sale_history, &mut items_trade_volumes);
+
+        items_trade_volumes.retain(|item_trade_volume| {
+            if item_trade_volume.forecast_velocity < thresholds.min_velocity {
+                rejected_items.low_velocity += 1;
+                return false;
+            }
+
+            if item_trade_volume.expected_profit < thresholds.min_expected_profit {
+                rejected_items.low_expected_profit += 1;
+                return false;
+            }
+
+            true
+        });
+
+        let listings_bar = self.progress_bar(
+            data_center_name,
+            "home world listings",
+            items_trade_volumes.chunks(90).len(),
+        );
+        let items_listings =
+            MarketImport::get_home_world_listings(home_world, &items_trade_volumes, &listings_bar)
+                .await?;
+        listings_bar.finish_and_clear();
+        MarketImport::apply_listing_saturation(&items_listings, &mut items_trade_volumes);
+        MarketImport::apply_manipulation_flags(
+            &items_sale_history,
+            &items_listings,
+            &mut items_trade_volumes,
+        );
+
+        let items_count = items_trade_volumes.len();
+
+        let trade_volumes_bar =
+            self.progress_bar(data_center_name, "trade volumes saved", items_count);
+        for items_trade_volumes_chunk in items_trade_volumes.chunks(ROWS_PER_WRITE) {
+            self.item_trades
+                .save_item_trade_volumes(sync_run_id, items_trade_volumes_chunk.to_vec())
+                .await?;
+            trade_volumes_bar.inc(items_trade_volumes_chunk.len() as u64);
+        }
+        trade_volumes_bar.finish_and_clear();
 
-        Ok(())
+        Ok(SyncSummary {
+            sync_run_id,
+            items_count,
+            rejected_items,
+            recommended_retainer_city: recommended_retainer_city.to_string(),
+        })
+    }
+
+    async fn import_tax_rates(
+        &self,
+        server: &DBServer,
+        sync_run_id: u64,
+    ) -> Result<HashMap<u64, TaxRates>, Error> {
+        let tax_rates_handlers = server
+            .worlds
+            .iter()
+            .map(|world| get_tax_rates_by_world(world.name.clone()));
+
+        let worlds_tax_rates = try_join_all(tax_rates_handlers).await?;
+
+        for (world, tax_rates) in server.worlds.iter().zip(worlds_tax_rates.iter()) {
+            self.tax_rate_data
+                .save_tax_rates(sync_run_id, world.world_id, tax_rates)
+                .await?;
+        }
+
+        Ok(server
+            .worlds
+            .iter()
+            .map(|world| world.world_id)
+            .zip(worlds_tax_rates)
+            .collect())
+    }
+
+    async fn get_home_world_listings(
+        home_world: &DBWorld,
+        items_trade_volumes: &[ItemTradeVolume],
+        progress_bar: &ProgressBar,
+    ) -> Result<HashMap<u64, ItemListingsMapping>, Error> {
+        let listings_handles: Vec<_> = items_trade_volumes
+            .chunks(90)
+            .map(|chunk| {
+                let chunk_ids = chunk
+                    .iter()
+                    .map(|item_trade_volume| item_trade_volume.item_id)
+                    .collect();
+
+                tokio::spawn(get_item_listings_by_world(
+                    chunk_ids,
+                    home_world.name.clone(),
+                ))
+            })
+            .collect();
+
+        let mut items_listings = HashMap::new();
+
+        for listings_handle in listings_handles {
+            items_listings.extend(listings_handle.await??.items);
+            progress_bar.inc(1);
+        }
+
+        Ok(items_listings)
+    }
+
+    async fn get_home_world_sale_history(
+        home_world: &DBWorld,
+        items_trade_volumes: &[ItemTradeVolume],
+        progress_bar: &ProgressBar,
+    ) -> Result<HashMap<u64, ItemMapping>, Error> {
+        let sale_history_handles: Vec<_> = items_trade_volumes
+            .chunks(90)
+            .map(|chunk| {
+                let chunk_ids = chunk
+                    .iter()
+                    .map(|item_trade_volume| item_trade_volume.item_id)
+                    .collect();
+
+                tokio::spawn(get_item_sale_history_by_world(
+                    chunk_ids,
+                    home_world.name.clone(),
+                    Some(FORECAST_HISTORY_DAYS * 24 * 60 * 60),
+                ))
+            })
+            .collect();
+
+        let mut items_sale_history = HashMap::new();
+
+        for sale_history_handle in sale_history_handles {
+            items_sale_history.extend(sale_history_handle.await??.items);
+            progress_bar.inc(1);
+        }
+
+        Ok(items_sale_history)
+    }
+
+    fn apply_listing_saturation(
+        items_listings: &HashMap<u64, ItemListingsMapping>,
+        items_trade_volumes: &mut [ItemTradeVolume],
+    ) {
+        let now = now_timestamp();
+
+        for item_trade_volume in items_trade_volumes.iter_mut() {
+            let Some(item_listings) = items_listings.get(&item_trade_volume.item_id) else {
+                continue;
+            };
+
+            let saturation =
+                get_listing_saturation(item_listings, item_trade_volume.forecast_velocity, now);
+
+            item_trade_volume.competing_retainers = saturation.competing_retainers;
+            item_trade_volume.days_of_supply = saturation.days_of_supply;
+            item_trade_volume.lowest_listing_changes = saturation.lowest_listing_changes;
+            item_trade_volume.saturation_score = saturation.saturation_score;
+        }
+    }
+
+    /// Replaces Universalis' velocity snapshot with the demand forecast from the home world sale history
+    fn apply_demand_forecast(
+        items_sale_history: &HashMap<u64, ItemMapping>,
+        items_trade_volumes: &mut [ItemTradeVolume],
+    ) {
+        let now = now_timestamp();
+
+        for item_trade_volume in items_trade_volumes.iter_mut() {
+            let Some(item_sale_history) = items_sale_history.get(&item_trade_volume.item_id) else {
+                continue;
+            };
+
+            item_trade_volume.forecast_velocity =
+                get_forecast_velocity(&item_sale_history.entries, now);
+            item_trade_volume.expected_profit =
+                item_trade_volume.unit_profit * item_trade_volume.forecast_velocity;
+        }
+    }
+
+    fn apply_manipulation_flags(
+        items_sale_history: &HashMap<u64, ItemMapping>,
+        items_listings: &HashMap<u64, ItemListingsMapping>,
+        items_trade_volumes: &mut [ItemTradeVolume],
+    ) {
+        let now = now_timestamp();
+
+        for item_trade_volume in items_trade_volumes.iter_mut() {
+            let sales = items_sale_history
+                .get(&item_trade_volume.item_id)
+                .map(|item_sale_history| &item_sale_history.entries[..])
+                .unwrap_or_default();
+            let listings = items_listings
+                .get(&item_trade_volume.item_id)
+                .map(|item_listings| &item_listings.listings[..])
+                .unwrap_or_default();
+
+            item_trade_volume.manipulation_flags = get_manipulation_flags(sales, listings, now);
+        }
     }
 
     async fn avg_item_prices_to_trade_volume(
         item_id: u64,
         home_world: DBWorld,
         lowest_avg_item_price: LowestAverageItemPrice,
+        purchase_price: f64,
+        data_age: u64,
+        is_stale: bool,
+        confidence_sample_count: f64,
     ) -> Result<ItemTradeVolume, Error> {
         let item_velocity = get_item_velocity_by_world(item_id, home_world.name.clone()).await?;
+        let sale_score = item_velocity.get_better_velocity();
+        let unit_profit = lowest_avg_item_price.home_world_price - purchase_price;
+        let sample_count = lowest_avg_item_price
+            .sample_size
+            .min(lowest_avg_item_price.home_world_sample_size);
+        let confidence_score = get_confidence_score(
+            sample_count,
+            lowest_avg_item_price
+                .price_cv
+                .max(lowest_avg_item_price.home_world_price_cv),
+            confidence_sample_count,
+        );
 
         Ok(ItemTradeVolume {
             item_id,
             world_id: home_world.world_id,
             cheapest_world_id: lowest_avg_item_price.world_id,
-            sale_score: item_velocity.get_better_velocity(),
+            sale_score,
             price_diff_score: lowest_avg_item_price.home_world_price / lowest_avg_item_price.price,
             home_world_avg_price: lowest_avg_item_price.home_world_price,
+            cheapest_world_avg_price: lowest_avg_item_price.price,
+            unit_profit,
+            forecast_velocity: sale_score,
+            expected_profit: unit_profit * sale_score,
+            competing_retainers: 0,
+            days_of_supply: 0.,
+            lowest_listing_changes: 0,
+            saturation_score: 0.,
+            price_std_dev: lowest_avg_item_price.home_world_price_std_dev,
+            price_cv: lowest_avg_item_price.home_world_price_cv,
+            sample_count: sample_count as u64,
+            confidence_score,
+            manipulation_flags: 0,
+            data_age,
+            is_stale,
         })
     }
 }
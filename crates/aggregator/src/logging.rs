@@ -0,0 +1,63 @@
+use clap::ValueEnum;
+use indicatif::{MultiProgress, ProgressDrawTarget};
+use std::io::{self, Write};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Writes log lines to stderr with the progress bars cleared, so they don't tear the bars
+#[derive(Clone)]
+struct ProgressWriter(MultiProgress);
+
+impl Write for ProgressWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.suspend(|| io::stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for ProgressWriter {
+    type Writer = ProgressWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Installs the global subscriber logging to stderr and returns the progress bars the syncs
+/// draw to. `RUST_LOG` replaces `level` when set. Progress bars are hidden with json logs
+pub fn init_logging(level: &str, format: LogFormat) -> MultiProgress {
+    // sqlx logs every query at info
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("{level},sqlx=warn")));
+
+    match format {
+        LogFormat::Text => {
+            let progress = MultiProgress::new();
+
+            tracing_subscriber::fmt()
+                .with_env_filter(filter)
+                .with_writer(ProgressWriter(progress.clone()))
+                .init();
+
+            progress
+        }
+        LogFormat::Json => {
+            tracing_subscriber::fmt()
+                .json()
+                .with_env_filter(filter)
+                .with_writer(io::stderr)
+                .init();
+
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        }
+    }
+}
@@ -1,18 +1,50 @@
+mod analysis;
+mod config;
 mod db;
 mod import;
+mod logging;
+mod notifications;
+mod report;
+mod tui;
 
-use crate::db::{ItemData, ItemTrades, ServerData};
-use crate::import::{ItemImport, MarketImport, ServerImport};
-use clap::{Args, Parser, Subcommand};
+use crate::analysis::{
+    estimate_days_to_sell, get_daily_sale_history, get_forecast_velocity, get_name_match_score,
+    get_price_advice, get_price_stats, get_price_trend, get_purchase_price, get_sale_timing,
+    now_timestamp, plan_purchases, plan_route, PlannedPurchase, PricePoint, PriceTrend,
+    PurchaseCandidate, ShoppingListItem, FORECAST_HISTORY_DAYS, FORECAST_HORIZON_DAYS, WEEKDAYS,
+};
+use crate::config::{replace, Config, ThresholdsConfig};
+use crate::db::{
+    DBItem, DBTradeOpportunity, ItemData, ItemFilter, ItemTrades, ServerData, SyncRunData,
+    TaxRateData, WatchlistData, WorldPriceData,
+};
+use crate::import::{
+    ItemImport, LiquidityThresholds, MarketImport, ServerImport, SyncSummary, TradeImportOptions,
+};
+use crate::logging::{init_logging, LogFormat};
+use crate::notifications::Notifier;
+use crate::report::{
+    diff_sync_runs, get_report, print_diff, print_report, render_html, write_chart, write_export,
+    write_workbook, ChartFormat, ChartSeries, ExportFormat, HtmlSection, ReportOptions, ReportSort,
+    WorkbookSheet,
+};
+use crate::tui::{run_tui, TuiData, TuiOptions};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use dotenv::dotenv;
-use serde::Deserialize;
+use futures::future::try_join_all;
+use futures::{stream, StreamExt};
+use indicatif::MultiProgress;
 use sqlx::mysql::MySqlPoolOptions;
+use sqlx::types::time::{OffsetDateTime, PrimitiveDateTime};
 use sqlx::{MySql, Pool};
-
-#[derive(Debug, Deserialize)]
-struct Config {
-    database_url: String,
-}
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+use tracing::{error, info, warn};
+use universalis_sdk::{
+    get_item_listings_by_world, get_item_sale_history_by_world, set_max_requests_per_second,
+};
 
 #[derive(Parser)]
 #[command(name = "FFXVI Market Board Aggregator")]
@@ -21,32 +53,589 @@ struct Config {
 #[command(about = "Aggregates data from market board api(Universalis https://docs.universalis.app/)", long_about = None)]
 #[command(propagate_version = true)]
 struct Cli {
+    /// Config file, defaults to `CONFIG_FILE` or `agregator.toml`
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Log level or filter directives, `RUST_LOG` takes precedence
+    #[arg(long, global = true, default_value = "info")]
+    log_level: String,
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+    #[command(flatten)]
+    config_overrides: ConfigOverridesArgs,
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Replace the config file and environment values, the other keys are overridden by the
+/// arguments of the commands using them
+#[derive(Args)]
+struct ConfigOverridesArgs {
+    #[arg(long, global = true)]
+    database_url: Option<String>,
+    /// Data center commands use when none is given
+    #[arg(long, global = true)]
+    data_center: Option<String>,
+    /// Home world per data center, e.g. `Light:Odin,Chaos:Omega`
+    #[arg(long, global = true, value_delimiter = ',')]
+    home_worlds: Option<Vec<String>>,
+    /// Worlds that are not open for travel, e.g. `Odin:congested,Phoenix:restricted`
+    #[arg(long, global = true, value_delimiter = ',')]
+    travel_statuses: Option<Vec<String>>,
+    /// Universalis requests per second over the whole process, 0 for no limit
+    #[arg(long, global = true)]
+    max_requests_per_second: Option<f64>,
+    /// Discord or Slack webhooks the sync summaries are posted to
+    #[arg(long, global = true, value_delimiter = ',')]
+    webhook_urls: Option<Vec<String>>,
+}
+
+impl ConfigOverridesArgs {
+    fn apply(&self, config: &mut Config) {
+        replace(&mut config.database_url, self.database_url.clone());
+        replace(&mut config.data_center, self.data_center.clone().map(Some));
+        replace(&mut config.home_worlds, self.home_worlds.clone());
+        replace(&mut config.travel_statuses, self.travel_statuses.clone());
+        replace(
+            &mut config.sync.max_requests_per_second,
+            self.max_requests_per_second,
+        );
+        replace(
+            &mut config.notifications.webhook_urls,
+            self.webhook_urls.clone(),
+        );
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     SyncBaseData,
     SyncTrades(SyncTradesArgs),
+    SyncRegion(SyncRegionArgs),
+    #[command(subcommand)]
+    Watchlist(WatchlistCommands),
+    PriceAdvice(PriceAdviceArgs),
+    Trending(TrendingArgs),
+    SellTiming(SellTimingArgs),
+    PlanPurchases(PlanPurchasesArgs),
+    Route(RouteArgs),
+    #[command(subcommand)]
+    WorldStatus(WorldStatusCommands),
+    Report(ReportArgs),
+    Export(ExportArgs),
+    ExportXlsx(ExportXlsxArgs),
+    RenderHtml(RenderHtmlArgs),
+    Item(ItemArgs),
+    Tui(TuiArgs),
+    Chart(ChartArgs),
+    SyncRuns(SyncRunsArgs),
+    Diff(DiffArgs),
+    #[command(subcommand)]
+    Config(ConfigCommands),
 }
 
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Prints the config merged from the config file, the environment and the arguments
+    Show,
+}
+
+/// Lists the latest sync runs of the home world
 #[derive(Args)]
-struct SyncTradesArgs {
-    data_center_name: String,
+struct SyncRunsArgs {
+    home_world_name: String,
+    #[arg(long, default_value_t = 10)]
+    limit: u64,
+}
+
+/// Compares the trade opportunities of two sync runs of the home world
+#[derive(Args)]
+struct DiffArgs {
+    home_world_name: String,
+    /// Defaults to the finished sync run before `--to`
+    #[arg(long)]
+    from: Option<u64>,
+    /// Defaults to the latest finished sync run
+    #[arg(long)]
+    to: Option<u64>,
+    #[command(flatten)]
+    report_filter: ReportFilterArgs,
+    /// Size of the top compared between the runs
+    #[arg(long, default_value_t = 25)]
+    limit: usize,
+    /// How many cheapest world, price and velocity changes are listed
+    #[arg(long, default_value_t = 10)]
+    changes: usize,
+}
+
+/// Renders the price and sales history of an item on every world of the data center
+#[derive(Args)]
+struct ChartArgs {
+    /// Item id or exact item name
+    item: String,
+    /// Svg or png file
+    output: PathBuf,
+    /// Defaults to the configured data center
+    data_center_name: Option<String>,
+    /// Defaults to the format of the output file extension
+    #[arg(long, value_enum)]
+    format: Option<ChartFormat>,
+    #[arg(long, default_value_t = 30)]
+    days: u32,
+    /// Only chart these worlds
+    #[arg(long = "world", value_delimiter = ',')]
+    worlds: Vec<String>,
+}
+
+/// Browse the latest trade opportunities of the home world interactively
+#[derive(Args)]
+struct TuiArgs {
+    home_world_name: String,
+    /// How many days of price history the chart shows
+    #[arg(long, default_value_t = 30)]
+    history_days: u32,
+    /// Watchlist items are added to with `w`
+    #[arg(long, default_value = "tui")]
+    watchlist: String,
+    /// Shopping list file for the route command items are appended to with `b`
+    #[arg(long, default_value = "shopping_list.txt")]
+    shopping_list: PathBuf,
+    /// Days of home world sales a shopping list entry buys
+    #[arg(long, default_value_t = FORECAST_HORIZON_DAYS as f64)]
+    days: f64,
+    #[command(flatten)]
+    report_filter: ReportFilterArgs,
+}
+
+#[derive(Args)]
+struct ItemArgs {
+    /// Item name, typos are tolerated
+    query: String,
+    /// Defaults to the configured data center
+    data_center_name: Option<String>,
+    /// How many other close matches are listed
+    #[arg(long, default_value_t = 5)]
+    alternatives: usize,
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    home_world_name: String,
+    #[command(flatten)]
+    report_filter: ReportFilterArgs,
+    #[arg(long, default_value_t = 25)]
+    limit: usize,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    home_world_name: String,
+    #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+    format: ExportFormat,
+    /// File to write to instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+    #[command(flatten)]
+    report_filter: ReportFilterArgs,
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+/// Writes a workbook with a sheet per data center home world configured in `HOME_WORLDS`
+#[derive(Args)]
+struct ExportXlsxArgs {
+    /// Defaults to the configured `export.xlsx_file`
+    output: Option<PathBuf>,
+    #[command(flatten)]
+    report_filter: ReportFilterArgs,
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+/// Renders a self-contained html page with a table per home world configured in `HOME_WORLDS`
+#[derive(Args)]
+struct RenderHtmlArgs {
+    /// Defaults to the configured `export.html_file`
+    output: Option<PathBuf>,
+    /// How many days of price history the sparklines show
+    #[arg(long, default_value_t = 30)]
+    days: u32,
+    #[command(flatten)]
+    report_filter: ReportFilterArgs,
+    #[arg(long, default_value_t = 100)]
+    limit: usize,
+}
+
+#[derive(Args)]
+struct ReportFilterArgs {
+    #[arg(long, value_enum, default_value_t = ReportSort::ExpectedProfit)]
+    sort: ReportSort,
+    /// Sort from the lowest value instead of the highest
+    #[arg(long)]
+    ascending: bool,
+    /// Minimum expected gil per day
+    #[arg(long)]
+    min_profit: Option<f64>,
+    /// Minimum forecast home world sales per day
+    #[arg(long)]
+    min_velocity: Option<f64>,
+    /// Only items from these market board search categories
+    #[arg(long = "search-category", value_delimiter = ',')]
+    search_category_ids: Vec<u64>,
+    /// Only items from these UI categories
+    #[arg(long = "ui-category", value_delimiter = ',')]
+    ui_category_ids: Vec<u64>,
+    /// Only items bought on this world
+    #[arg(long)]
+    cheapest_world: Option<String>,
+    #[command(flatten)]
+    opportunity_filter: OpportunityFilterArgs,
+}
+
+impl ReportFilterArgs {
+    fn to_options(&self, limit: Option<usize>) -> ReportOptions {
+        ReportOptions {
+            sort: self.sort,
+            ascending: self.ascending,
+            min_profit: self.min_profit,
+            min_velocity: self.min_velocity,
+            search_category_ids: self.search_category_ids.clone(),
+            ui_category_ids: self.ui_category_ids.clone(),
+            cheapest_world_name: self.cheapest_world.clone(),
+            include_suspicious: self.opportunity_filter.include_suspicious,
+            include_stale: self.opportunity_filter.include_stale,
+            limit,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum WorldStatusCommands {
+    Set(WorldStatusSetArgs),
+    /// Replaces all travel statuses with the `TRAVEL_STATUSES` list or a file of `world:status` lines
+    Import(WorldStatusImportArgs),
+    Show(WorldStatusShowArgs),
+}
+
+#[derive(Args)]
+struct WorldStatusSetArgs {
+    world_name: String,
+    #[arg(value_enum)]
+    status: TravelStatus,
+}
+
+#[derive(Args)]
+struct WorldStatusImportArgs {
+    #[arg(long)]
+    file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct WorldStatusShowArgs {
+    /// Defaults to the configured data center
+    data_center_name: Option<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum TravelStatus {
+    Open,
+    Congested,
+    Restricted,
+}
+
+impl TravelStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TravelStatus::Open => "open",
+            TravelStatus::Congested => "congested",
+            TravelStatus::Restricted => "restricted",
+        }
+    }
+}
+
+#[derive(Args)]
+struct PlanPurchasesArgs {
+    home_world_name: String,
+    /// Gil available for purchases
+    #[arg(long)]
+    budget: u64,
+    /// Free inventory slots
+    #[arg(long)]
+    slots: usize,
+    /// Days the purchased items should sell out in on the home world
+    #[arg(long, default_value_t = FORECAST_HORIZON_DAYS as f64)]
+    days: f64,
+    #[command(flatten)]
+    opportunity_filter: OpportunityFilterArgs,
+}
+
+#[derive(Args)]
+struct RouteArgs {
+    home_world_name: String,
+    /// Item id or exact item name with an optional quantity, e.g. `--item "Darksteel Ore:99"`
+    #[arg(long = "item")]
+    items: Vec<String>,
+    /// Shopping list file with one `item[:quantity]` per line
+    #[arg(long)]
+    file: Option<PathBuf>,
+    /// Plan the shopping list from the latest trade opportunities with this budget
+    #[arg(long, requires = "slots", required_unless_present_any = ["items", "file"])]
+    budget: Option<u64>,
+    #[arg(long, requires = "budget")]
+    slots: Option<usize>,
+    #[arg(long, default_value_t = FORECAST_HORIZON_DAYS as f64)]
+    days: f64,
+    /// Gil a world transfer is worth, a pricier world already on the route is preferred up to it
+    #[arg(long)]
+    transfer_cost: Option<f64>,
+    /// Gil added to the transfer cost of congested worlds
+    #[arg(long)]
+    congested_transfer_cost: Option<f64>,
+    #[command(flatten)]
+    opportunity_filter: OpportunityFilterArgs,
+}
+
+#[derive(Args)]
+struct OpportunityFilterArgs {
+    /// Include items flagged for market manipulation
+    #[arg(long)]
+    include_suspicious: bool,
+    /// Include items synced from stale Universalis data
+    #[arg(long)]
+    include_stale: bool,
+}
+
+impl OpportunityFilterArgs {
+    fn matches(&self, trade_opportunity: &DBTradeOpportunity) -> bool {
+        (self.include_suspicious || !trade_opportunity.is_suspicious())
+            && (self.include_stale || !trade_opportunity.is_stale)
+    }
+}
+
+#[derive(Args)]
+struct SellTimingArgs {
+    /// Item id or exact item name
+    item: String,
+    home_world_name: String,
+    /// How many days of sales are aggregated
+    #[arg(long, default_value_t = 28)]
+    days: u64,
+    /// Hours added to UTC sale times, e.g. 2 for CEST
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    utc_offset: i64,
+}
+
+#[derive(Args)]
+struct TrendingArgs {
     home_world_name: String,
+    /// How many days of sync runs the trend is computed over
+    #[arg(long, default_value_t = 14)]
+    days: u32,
+    #[arg(long, value_enum, default_value_t = TrendDirection::Rising)]
+    direction: TrendDirection,
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+    /// Include items flagged for market manipulation
+    #[arg(long)]
+    include_suspicious: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum TrendDirection {
+    Rising,
+    Falling,
+}
+
+#[derive(Args)]
+struct PriceAdviceArgs {
+    /// Item id or exact item name
+    item: String,
+    home_world_name: String,
+}
+
+#[derive(Subcommand)]
+enum WatchlistCommands {
+    Add(WatchlistItemsArgs),
+    Remove(WatchlistItemsArgs),
+    Show(WatchlistArgs),
+}
+
+#[derive(Args)]
+struct LiquidityThresholdsArgs {
+    /// Minimum forecast home world sales per day
+    #[arg(long)]
+    min_velocity: Option<f64>,
+    /// Minimum number of sales on both the home world and the cheapest world
+    #[arg(long)]
+    min_sample_size: Option<usize>,
+    #[arg(long)]
+    min_home_world_price: Option<f64>,
+    /// Minimum expected gil per day from reselling on the home world
+    #[arg(long)]
+    min_expected_profit: Option<f64>,
+}
+
+impl LiquidityThresholdsArgs {
+    fn to_thresholds(&self, config: &ThresholdsConfig) -> LiquidityThresholds {
+        LiquidityThresholds {
+            min_velocity: self.min_velocity.unwrap_or(config.min_velocity),
+            min_sample_size: self.min_sample_size.unwrap_or(config.min_sample_size),
+            min_home_world_price: self
+                .min_home_world_price
+                .unwrap_or(config.min_home_world_price),
+            min_expected_profit: self
+                .min_expected_profit
+                .unwrap_or(config.min_expected_profit),
+        }
+    }
+}
+
+#[derive(Args)]
+struct WatchlistItemsArgs {
+    name: String,
+    #[arg(required = true)]
+    item_ids: Vec<u64>,
+}
+
+#[derive(Args)]
+struct WatchlistArgs {
+    name: String,
+}
+
+#[derive(Args)]
+struct ItemFilterArgs {
+    /// Only items from these market board search categories
+    #[arg(long = "search-category", value_delimiter = ',')]
+    search_category_ids: Vec<u64>,
+    /// Only items from these UI categories
+    #[arg(long = "ui-category", value_delimiter = ',')]
+    ui_category_ids: Vec<u64>,
+    #[arg(long)]
+    min_ilvl: Option<u64>,
+    #[arg(long)]
+    max_ilvl: Option<u64>,
+    /// Regular expression matched against item names
+    #[arg(long)]
+    name: Option<String>,
+    /// Only items with these ids
+    #[arg(long = "item", value_delimiter = ',')]
+    item_ids: Vec<u64>,
+    /// Only items from this watchlist
+    #[arg(long)]
+    watchlist: Option<String>,
+}
+
+impl From<&ItemFilterArgs> for ItemFilter {
+    fn from(args: &ItemFilterArgs) -> Self {
+        Self {
+            search_category_ids: args.search_category_ids.clone(),
+            ui_category_ids: args.ui_category_ids.clone(),
+            min_item_level: args.min_ilvl,
+            max_item_level: args.max_ilvl,
+            name_pattern: args.name.clone(),
+            item_ids: args.item_ids.clone(),
+            watchlist: args.watchlist.clone(),
+        }
+    }
+}
+
+#[derive(Args)]
+struct SyncTradesArgs {
+    /// Defaults to the configured data center
+    data_center_name: Option<String>,
+    /// Defaults to the configured home world of the data center
+    home_world_name: Option<String>,
+    #[command(flatten)]
+    trade_import: TradeImportArgs,
+}
+
+#[derive(Args)]
+struct SyncRegionArgs {
+    region: String,
+    /// How many data centers are synced at the same time
+    #[arg(long)]
+    parallelism: Option<usize>,
+    #[command(flatten)]
+    trade_import: TradeImportArgs,
+}
+
+#[derive(Args)]
+struct TradeImportArgs {
+    #[command(flatten)]
+    item_filter: ItemFilterArgs,
+    #[command(flatten)]
+    liquidity_thresholds: LiquidityThresholdsArgs,
+    /// Hours after which Universalis data of a world is marked as stale
+    #[arg(long)]
+    max_data_age_hours: Option<u64>,
+    /// Skip items whose home world or cheapest world data is stale
+    #[arg(long, overrides_with = "include_stale")]
+    exclude_stale: bool,
+    /// Keep items with stale data even when the config excludes them
+    #[arg(long, overrides_with = "exclude_stale")]
+    include_stale: bool,
+    /// Percent a congested world has to be cheaper by to be picked as the cheapest world
+    #[arg(long)]
+    congested_world_penalty: Option<f64>,
+    /// Sales at which the confidence score reaches ~63% of its maximum
+    #[arg(long)]
+    confidence_sample_count: Option<f64>,
+    /// Per item Universalis requests in flight at the same time
+    #[arg(long)]
+    max_concurrent_requests: Option<usize>,
+}
+
+impl TradeImportArgs {
+    fn to_options(&self, config: &Config) -> TradeImportOptions {
+        TradeImportOptions {
+            item_filter: ItemFilter::from(&self.item_filter),
+            liquidity_thresholds: self.liquidity_thresholds.to_thresholds(&config.thresholds),
+            max_data_age: self
+                .max_data_age_hours
+                .unwrap_or(config.sync.max_data_age_hours)
+                * 60
+                * 60,
+            exclude_stale: match (self.exclude_stale, self.include_stale) {
+                (true, _) => true,
+                (_, true) => false,
+                _ => config.sync.exclude_stale,
+            },
+            congested_world_penalty: self
+                .congested_world_penalty
+                .unwrap_or(config.scoring.congested_world_penalty),
+            confidence_sample_count: self
+                .confidence_sample_count
+                .unwrap_or(config.scoring.confidence_sample_count),
+            max_concurrent_requests: self
+                .max_concurrent_requests
+                .unwrap_or(config.sync.max_concurrent_requests),
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
 
-    let config = match envy::from_env::<Config>() {
+    let cli = Cli::parse();
+    let progress = init_logging(&cli.log_level, cli.log_format);
+
+    let mut config = match Config::load(cli.config.as_deref()) {
         Ok(config) => config,
         Err(error) => panic!("{error:#?}"),
     };
+    cli.config_overrides.apply(&mut config);
 
-    let cli = Cli::parse();
+    if let Commands::Config(ConfigCommands::Show) = &cli.command {
+        print!("{}", config.to_toml().unwrap());
+        return;
+    }
+
+    if config.database_url.is_empty() {
+        panic!("DATABASE_URL is not set in the environment or the config file");
+    }
+
+    set_max_requests_per_second(config.sync.max_requests_per_second);
 
     let pool = MySqlPoolOptions::new()
         .max_connections(5)
@@ -59,43 +648,1436 @@ async fn main() {
             sync_base_data(pool).await;
         }
         Commands::SyncTrades(args) => {
-            sync_trades(args, pool).await;
+            sync_trades(args, &config, pool, progress).await;
+        }
+        Commands::SyncRegion(args) => {
+            sync_region(args, &config, pool, progress).await;
+        }
+        Commands::Watchlist(command) => {
+            watchlist(command, pool).await;
+        }
+        Commands::PriceAdvice(args) => {
+            price_advice(args, pool).await;
+        }
+        Commands::Trending(args) => {
+            trending(args, pool).await;
+        }
+        Commands::SellTiming(args) => {
+            sell_timing(args, pool).await;
         }
+        Commands::PlanPurchases(args) => {
+            plan_purchases_list(args, pool).await;
+        }
+        Commands::Route(args) => {
+            route(args, &config, pool).await;
+        }
+        Commands::WorldStatus(command) => {
+            world_status(command, &config, pool).await;
+        }
+        Commands::Report(args) => {
+            report(args, pool).await;
+        }
+        Commands::Export(args) => {
+            export(args, pool).await;
+        }
+        Commands::ExportXlsx(args) => {
+            export_xlsx(args, &config, pool).await;
+        }
+        Commands::RenderHtml(args) => {
+            render_html_report(args, &config, pool).await;
+        }
+        Commands::Item(args) => {
+            item(args, &config, pool).await;
+        }
+        Commands::Tui(args) => {
+            tui(args, pool).await;
+        }
+        Commands::Chart(args) => {
+            chart(args, &config, pool).await;
+        }
+        Commands::SyncRuns(args) => {
+            sync_runs(args, pool).await;
+        }
+        Commands::Diff(args) => {
+            diff(args, pool).await;
+        }
+        Commands::Config(_) => {}
     }
 }
 
 async fn sync_base_data(pool: Pool<MySql>) {
-    println!("Importing items data ...");
+    info!("Importing items data");
     let item_data = ItemData::new(pool.clone());
     let item_import = ItemImport::new(item_data);
     item_import.import_marketable_items().await.unwrap();
-    println!("Done!");
 
-    println!("Syncing items data ...");
+    info!("Syncing items data");
     item_import.sync_items().await.unwrap();
-    println!("Done!");
 
-    println!("Importing servers data ...");
+    info!("Importing servers data");
     let server_data = ServerData::new(pool.clone());
     let server_import = ServerImport::new(server_data);
     server_import.import_servers().await.unwrap();
-    println!("Done!");
 
-    println!("Base data successfully synced!");
+    info!("Base data successfully synced");
+}
+
+fn market_import(pool: Pool<MySql>, progress: MultiProgress) -> MarketImport {
+    let item_trades = ItemTrades::new(pool.clone());
+    let server_data = ServerData::new(pool.clone());
+    let item_data = ItemData::new(pool.clone());
+    let sync_run_data = SyncRunData::new(pool.clone());
+    let tax_rate_data = TaxRateData::new(pool.clone());
+    let world_price_data = WorldPriceData::new(pool);
+
+    MarketImport::new(
+        item_trades,
+        server_data,
+        item_data,
+        sync_run_data,
+        tax_rate_data,
+        world_price_data,
+        progress,
+    )
+}
+
+async fn sync_trades(
+    args: &SyncTradesArgs,
+    config: &Config,
+    pool: Pool<MySql>,
+    progress: MultiProgress,
+) {
+    let Some(data_center_name) = config.data_center_name(args.data_center_name.as_ref()) else {
+        error!("No data center given or configured");
+        return;
+    };
+    let home_world_name = match &args.home_world_name {
+        Some(home_world_name) => home_world_name.clone(),
+        None => match config
+            .home_worlds_by_data_center()
+            .remove(&data_center_name)
+        {
+            Some(home_world_name) => home_world_name,
+            None => {
+                error!(data_center = %data_center_name, "No home world given or configured");
+                return;
+            }
+        },
+    };
+
+    let market_imports = market_import(pool, progress);
+
+    info!(data_center = %data_center_name, home_world = %home_world_name, "Importing trades data");
+    let summary = market_imports
+        .import_market_trade_volumes(
+            data_center_name.clone(),
+            home_world_name,
+            &args.trade_import.to_options(config),
+        )
+        .await
+        .unwrap();
+    report_sync_summary(
+        &data_center_name,
+        &summary,
+        &Notifier::new(&config.notifications),
+    )
+    .await;
+
+    info!("Trades data successfully synced");
+}
+
+async fn sync_region(
+    args: &SyncRegionArgs,
+    config: &Config,
+    pool: Pool<MySql>,
+    progress: MultiProgress,
+) {
+    let server_data = ServerData::new(pool.clone());
+    let data_centers = server_data
+        .get_region_data_centers(args.region.clone())
+        .await
+        .unwrap();
+    let home_worlds = config.home_worlds_by_data_center();
+    let options = args.trade_import.to_options(config);
+    let notifier = &Notifier::new(&config.notifications);
+
+    let data_centers_imports = data_centers
+        .into_iter()
+        .filter_map(|data_center| match home_worlds.get(&data_center.name) {
+            Some(home_world_name) => Some((data_center.name, home_world_name.clone())),
+            None => {
+                warn!(data_center = %data_center.name, "No home world configured, skipping");
+                None
+            }
+        });
+
+    info!(region = %args.region, "Importing trades data for the region");
+    stream::iter(data_centers_imports)
+        .map(|(data_center_name, home_world_name)| {
+            let market_imports = market_import(pool.clone(), progress.clone());
+            let options = &options;
+
+            async move {
+                info!(data_center = %data_center_name, home_world = %home_world_name, "Importing trades data");
+                let result = market_imports
+                    .import_market_trade_volumes(data_center_name.clone(), home_world_name, options)
+                    .await;
+
+                (data_center_name, result)
+            }
+        })
+        .buffer_unordered(args.parallelism.unwrap_or(config.sync.parallelism).max(1))
+        .for_each(|(data_center_name, result)| async move {
+            match result {
+                Ok(summary) => report_sync_summary(&data_center_name, &summary, notifier).await,
+                Err(error) => {
+                    error!(data_center = %data_center_name, ?error, "Sync failed");
+                    notifier
+                        .notify(&format!("Sync of {data_center_name} failed: {error}"))
+                        .await;
+                }
+            }
+        })
+        .await;
+
+    info!(region = %args.region, "Region trades data synced");
+}
+
+async fn world_status(command: &WorldStatusCommands, config: &Config, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool);
+
+    match command {
+        WorldStatusCommands::Set(args) => {
+            let updated = server_data
+                .set_travel_status(&args.world_name, args.status.as_str())
+                .await
+                .unwrap();
+
+            match updated {
+                0 => println!("No world named {}", args.world_name),
+                _ => println!("{} is {}", args.world_name, args.status.as_str()),
+            }
+        }
+        WorldStatusCommands::Import(args) => {
+            let travel_statuses = match &args.file {
+                Some(file) => fs::read_to_string(file)
+                    .unwrap()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string)
+                    .collect(),
+                None => config.travel_statuses.clone(),
+            };
+
+            server_data.reset_travel_statuses().await.unwrap();
+
+            for travel_status in travel_statuses {
+                let Some((world_name, status)) = travel_status.split_once(':') else {
+                    println!("Skipping {travel_status}, expected world:status");
+                    continue;
+                };
+
+                let Ok(status) = TravelStatus::from_str(status.trim(), true) else {
+                    println!("Skipping {travel_status}, unknown status");
+                    continue;
+                };
+
+                let updated = server_data
+                    .set_travel_status(world_name.trim(), status.as_str())
+                    .await
+                    .unwrap();
+
+                match updated {
+                    0 => println!("No world named {}", world_name.trim()),
+                    _ => println!("{} is {}", world_name.trim(), status.as_str()),
+                }
+            }
+        }
+        WorldStatusCommands::Show(args) => {
+            let Some(data_center_name) = config.data_center_name(args.data_center_name.as_ref())
+            else {
+                println!("No data center given or configured");
+                return;
+            };
+            let server = server_data.get_server(data_center_name).await.unwrap();
+
+            for world in server.worlds {
+                println!("{:<20} {}", world.name, world.travel_status);
+            }
+        }
+    }
+}
+
+/// Logs the sync summary and posts it to the notification webhooks
+async fn report_sync_summary(data_center_name: &str, summary: &SyncSummary, notifier: &Notifier) {
+    let rejected_items = &summary.rejected_items;
+
+    info!(
+        data_center = data_center_name,
+        sync_run_id = summary.sync_run_id,
+        items_count = summary.items_count,
+        rejected_count = rejected_items.total(),
+        rejected_no_eligible_sales = rejected_items.no_eligible_sales,
+        rejected_no_home_world_sales = rejected_items.no_home_world_sales,
+        rejected_low_velocity = rejected_items.low_velocity,
+        rejected_low_sample_size = rejected_items.low_sample_size,
+        rejected_low_home_world_price = rejected_items.low_home_world_price,
+        rejected_low_expected_profit = rejected_items.low_expected_profit,
+        rejected_stale_data = rejected_items.stale_data,
+        retainer_city = %summary.recommended_retainer_city,
+        "Sync run #{} saved {} items, list in {} for the lowest tax",
+        summary.sync_run_id,
+        summary.items_count,
+        summary.recommended_retainer_city,
+    );
+
+    notifier
+        .notify(&format!(
+            "Sync run #{} of {} saved {} items, {} rejected, list in {} for the lowest tax",
+            summary.sync_run_id,
+            data_center_name,
+            summary.items_count,
+            rejected_items.total(),
+            summary.recommended_retainer_city,
+        ))
+        .await;
+}
+
+async fn watchlist(command: &WatchlistCommands, pool: Pool<MySql>) {
+    let watchlist_data = WatchlistData::new(pool);
+
+    match command {
+        WatchlistCommands::Add(args) => {
+            watchlist_data
+                .add_items(args.name.clone(), args.item_ids.clone())
+                .await
+                .unwrap();
+            println!("Added {} items to {}", args.item_ids.len(), args.name);
+        }
+        WatchlistCommands::Remove(args) => {
+            watchlist_data
+                .remove_items(args.name.clone(), args.item_ids.clone())
+                .await
+                .unwrap();
+            println!("Removed {} items from {}", args.item_ids.len(), args.name);
+        }
+        WatchlistCommands::Show(args) => {
+            let items = watchlist_data.get_items(args.name.clone()).await.unwrap();
+            for item in items {
+                println!("{}\t{}", item.item_id, item.name);
+            }
+        }
+    }
+}
+
+async fn price_advice(args: &PriceAdviceArgs, pool: Pool<MySql>) {
+    let item_data = ItemData::new(pool);
+    let item = item_data.get_item(&args.item).await.unwrap();
+
+    let (item_listings, sale_history) = tokio::try_join!(
+        get_item_listings_by_world(vec![item.item_id], args.home_world_name.clone()),
+        get_item_sale_history_by_world(
+            vec![item.item_id],
+            args.home_world_name.clone(),
+            Some(FORECAST_HISTORY_DAYS * 24 * 60 * 60),
+        ),
+    )
+    .unwrap();
+
+    let listings = item_listings
+        .items
+        .get(&item.item_id)
+        .map(|item_listings| item_listings.listings.clone())
+        .unwrap_or_default();
+    let sales = sale_history
+        .items
+        .get(&item.item_id)
+        .map(|item_sale_history| item_sale_history.entries.clone())
+        .unwrap_or_default();
+    let velocity = get_forecast_velocity(&sales, now_timestamp());
+
+    println!("{} on {}", item.name, args.home_world_name);
+
+    let Some(advice) = get_price_advice(&listings, &sales) else {
+        println!("No listings or sales to base the advice on");
+        return;
+    };
+
+    if let Some(lowest_listing_price) = advice.lowest_listing_price {
+        println!("Lowest listing: {lowest_listing_price} gil");
+    }
+    if let Some(median_sale_price) = advice.median_sale_price {
+        println!("Median sale price: {median_sale_price} gil");
+    }
+    println!("Recommended price: {} gil", advice.recommended_price);
+    println!("Forecast sales per day: {velocity:.2}");
+
+    println!("Stack sizes that sell:");
+    for stack_size in advice.stack_sizes.iter().take(5) {
+        let days_to_sell = estimate_days_to_sell(stack_size.quantity, velocity)
+            .map(|days| format!("{days:.1} days to sell"))
+            .unwrap_or_else(|| "no recent sales".to_string());
+
+        println!(
+            "  x{:<4} {:>5.1}% of sales, {}",
+            stack_size.quantity,
+            stack_size.share * 100.,
+            days_to_sell
+        );
+    }
+}
+
+async fn trending(args: &TrendingArgs, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let world_price_data = WorldPriceData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool);
+
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
+        .await
+        .unwrap();
+    let world_prices = world_price_data
+        .get_world_price_history(home_world.world_id, args.days)
+        .await
+        .unwrap();
+    let suspicious_items_ids: HashSet<u64> = if args.include_suspicious {
+        HashSet::new()
+    } else {
+        item_trades
+            .get_suspicious_items_ids(home_world.world_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect()
+    };
+
+    let mut items_points: HashMap<u64, (String, Vec<PricePoint>)> = HashMap::new();
+
+    for world_price in world_prices {
+        if suspicious_items_ids.contains(&world_price.item_id) {
+            continue;
+        }
+
+        let (_, points) = items_points
+            .entry(world_price.item_id)
+            .or_insert_with(|| (world_price.item_name, Vec::new()));
+
+        points.push(PricePoint {
+            day: world_price.recorded_at.assume_utc().unix_timestamp() as f64 / 86400.,
+            price: world_price.avg_price,
+        });
+    }
+
+    let mut trends: Vec<(String, PriceTrend)> = items_points
+        .into_values()
+        .filter_map(|(item_name, points)| Some((item_name, get_price_trend(&points)?)))
+        .filter(|(_, trend)| match args.direction {
+            TrendDirection::Rising => trend.slope > 0.,
+            TrendDirection::Falling => trend.slope < 0.,
+        })
+        .collect();
+
+    // Sort by the relative daily change so cheap and expensive items are comparable
+    trends.sort_by(|(_, a), (_, b)| {
+        let a_change = a.slope / a.latest_price.max(1.);
+        let b_change = b.slope / b.latest_price.max(1.);
+        match args.direction {
+            TrendDirection::Rising => b_change.total_cmp(&a_change),
+            TrendDirection::Falling => a_change.total_cmp(&b_change),
+        }
+    });
+
+    println!(
+        "{:<40} {:>12} {:>14} {:>10}",
+        "Item", "Price", "Gil per day", "WoW %"
+    );
+    for (item_name, trend) in trends.iter().take(args.limit) {
+        let week_over_week_change = trend
+            .week_over_week_change
+            .map(|change| format!("{change:+.1}"))
+            .unwrap_or_else(|| "-".to_string());
+
+        println!(
+            "{:<40} {:>12.0} {:>+14.1} {:>10}",
+            item_name, trend.latest_price, trend.slope, week_over_week_change
+        );
+    }
+}
+
+async fn sell_timing(args: &SellTimingArgs, pool: Pool<MySql>) {
+    let item_data = ItemData::new(pool);
+    let item = item_data.get_item(&args.item).await.unwrap();
+
+    let sale_history = get_item_sale_history_by_world(
+        vec![item.item_id],
+        args.home_world_name.clone(),
+        Some(args.days * 24 * 60 * 60),
+    )
+    .await
+    .unwrap();
+    let sales = sale_history
+        .items
+        .get(&item.item_id)
+        .map(|item_sale_history| item_sale_history.entries.clone())
+        .unwrap_or_default();
+
+    println!(
+        "{} on {}, {} sales in the last {} days (UTC{:+})",
+        item.name,
+        args.home_world_name,
+        sales.len(),
+        args.days,
+        args.utc_offset
+    );
+
+    let Some(timing) = get_sale_timing(&sales, args.utc_offset) else {
+        println!("No sales to base the timing on");
+        return;
+    };
+
+    let peak_hour = timing.peak_hour();
+    println!(
+        "Demand peaks on {} around {:02}:00-{:02}:00",
+        WEEKDAYS[timing.peak_weekday()],
+        peak_hour,
+        (peak_hour + 1) % 24
+    );
+
+    println!("By day of week:");
+    for (weekday, share) in WEEKDAYS.iter().zip(timing.by_weekday) {
+        println!(
+            "  {:<10} {:>5.1}% {}",
+            weekday,
+            share * 100.,
+            share_bar(share)
+        );
+    }
+
+    println!("By hour of day:");
+    for (hour, share) in timing.by_hour.iter().enumerate() {
+        println!(
+            "  {:02}:00      {:>5.1}% {}",
+            hour,
+            share * 100.,
+            share_bar(*share)
+        );
+    }
+}
+
+fn share_bar(share: f64) -> String {
+    "#".repeat((share * 100.).round() as usize)
+}
+
+async fn plan_purchases_list(args: &PlanPurchasesArgs, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool);
+
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
+        .await
+        .unwrap();
+    let planned_purchases = get_purchase_plan(
+        &item_trades,
+        home_world.world_id,
+        args.budget,
+        args.slots,
+        args.days,
+        &args.opportunity_filter,
+    )
+    .await;
+
+    if planned_purchases.is_empty() {
+        println!("Nothing worth buying within the budget");
+        return;
+    }
+
+    let mut worlds_purchases: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+
+    for (trade_opportunity, planned_purchase) in &planned_purchases {
+        worlds_purchases
+            .entry(&trade_opportunity.cheapest_world_name)
+            .or_default()
+            .push((trade_opportunity, planned_purchase));
+    }
+
+    for (world_name, purchases) in worlds_purchases {
+        println!("{world_name}:");
+        for (trade_opportunity, planned_purchase) in purchases {
+            println!(
+                "  {:<40} x{:<5} {:>10.0} gil each {:>12.0} gil, expected profit {:>12.0} gil",
+                trade_opportunity.item_name,
+                planned_purchase.quantity,
+                trade_opportunity.unit_cost(),
+                planned_purchase.cost,
+                planned_purchase.expected_profit
+            );
+        }
+    }
+
+    let total_cost = planned_purchases
+        .iter()
+        .map(|(_, planned_purchase)| planned_purchase.cost)
+        .sum::<f64>();
+    let total_profit = planned_purchases
+        .iter()
+        .map(|(_, planned_purchase)| planned_purchase.expected_profit)
+        .sum::<f64>();
+
+    println!("Total: {total_cost:.0} gil, expected profit {total_profit:.0} gil");
+}
+
+/// Shopping list optimised over the latest trade opportunities of the home world
+async fn get_purchase_plan(
+    item_trades: &ItemTrades,
+    home_world_id: u64,
+    budget: u64,
+    slots: usize,
+    days: f64,
+    opportunity_filter: &OpportunityFilterArgs,
+) -> Vec<(DBTradeOpportunity, PlannedPurchase)> {
+    let trade_opportunities: Vec<DBTradeOpportunity> = item_trades
+        .get_trade_opportunities(home_world_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|trade_opportunity| opportunity_filter.matches(trade_opportunity))
+        .collect();
+
+    let candidates: Vec<PurchaseCandidate> = trade_opportunities
+        .iter()
+        .map(|trade_opportunity| PurchaseCandidate {
+            item_id: trade_opportunity.item_id,
+            unit_cost: trade_opportunity.unit_cost(),
+            unit_profit: trade_opportunity.unit_profit,
+            max_quantity: (trade_opportunity.forecast_velocity * days).floor() as u64,
+            stack_size: trade_opportunity.stack_size,
+        })
+        .collect();
+
+    let mut trade_opportunities: HashMap<u64, DBTradeOpportunity> = trade_opportunities
+        .into_iter()
+        .map(|trade_opportunity| (trade_opportunity.item_id, trade_opportunity))
+        .collect();
+
+    plan_purchases(&candidates, budget as f64, slots)
+        .into_iter()
+        .filter_map(|planned_purchase| {
+            let trade_opportunity = trade_opportunities.remove(&planned_purchase.item_id)?;
+            Some((trade_opportunity, planned_purchase))
+        })
+        .collect()
+}
+
+async fn route(args: &RouteArgs, config: &Config, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let item_data = ItemData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool.clone());
+    let sync_run_data = SyncRunData::new(pool.clone());
+    let tax_rate_data = TaxRateData::new(pool.clone());
+    let world_price_data = WorldPriceData::new(pool);
+
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
+        .await
+        .unwrap();
+
+    let mut items_names: HashMap<u64, String> = HashMap::new();
+    let mut shopping_list: Vec<ShoppingListItem> = Vec::new();
+
+    if let (Some(budget), Some(slots)) = (args.budget, args.slots) {
+        let planned_purchases = get_purchase_plan(
+            &item_trades,
+            home_world.world_id,
+            budget,
+            slots,
+            args.days,
+            &args.opportunity_filter,
+        )
+        .await;
+
+        for (trade_opportunity, planned_purchase) in planned_purchases {
+            items_names.insert(trade_opportunity.item_id, trade_opportunity.item_name);
+            shopping_list.push(ShoppingListItem {
+                item_id: planned_purchase.item_id,
+                quantity: planned_purchase.quantity,
+            });
+        }
+    }
+
+    let mut entries = args.items.clone();
+    if let Some(file) = &args.file {
+        let content = fs::read_to_string(file).unwrap();
+        entries.extend(
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string),
+        );
+    }
+
+    for entry in entries {
+        let (item, quantity) = parse_shopping_list_entry(&entry);
+        let item = item_data.get_item(item).await.unwrap();
+
+        items_names.insert(item.item_id, item.name);
+        shopping_list.push(ShoppingListItem {
+            item_id: item.item_id,
+            quantity,
+        });
+    }
+
+    let Some(sync_run_id) = sync_run_data
+        .get_latest_finished_sync_run_id(home_world.world_id)
+        .await
+        .unwrap()
+    else {
+        println!("No finished sync run for {}", home_world.name);
+        return;
+    };
+
+    let worlds = server_data
+        .get_data_center_worlds(home_world.data_center_id)
+        .await
+        .unwrap();
+
+    let transfer_cost = args.transfer_cost.unwrap_or(config.scoring.transfer_cost);
+    let congested_transfer_cost = args
+        .congested_transfer_cost
+        .unwrap_or(config.scoring.congested_transfer_cost);
+
+    // Restricted worlds get no transfer cost so the route never goes there
+    let transfer_costs: HashMap<u64, f64> = worlds
+        .iter()
+        .filter_map(|world| {
+            let transfer_cost = if world.world_id == home_world.world_id {
+                0.
+            } else if world.is_restricted() {
+                return None;
+            } else if world.is_congested() {
+                transfer_cost + congested_transfer_cost
+            } else {
+                transfer_cost
+            };
+
+            Some((world.world_id, transfer_cost))
+        })
+        .collect();
+
+    let worlds_names: HashMap<u64, String> = worlds
+        .into_iter()
+        .map(|world| (world.world_id, world.name))
+        .collect();
+
+    let mut worlds_tax_rates: HashMap<u64, Vec<u64>> = HashMap::new();
+    for tax_rate in tax_rate_data.get_tax_rates(sync_run_id).await.unwrap() {
+        worlds_tax_rates
+            .entry(tax_rate.world_id)
+            .or_default()
+            .push(tax_rate.rate);
+    }
+
+    let mut unit_costs: HashMap<u64, HashMap<u64, f64>> = HashMap::new();
+    for world_price in world_price_data
+        .get_world_prices(sync_run_id)
+        .await
+        .unwrap()
+    {
+        let cities_rates = worlds_tax_rates
+            .get(&world_price.world_id)
+            .into_iter()
+            .flatten()
+            .copied();
+
+        unit_costs.entry(world_price.item_id).or_default().insert(
+            world_price.world_id,
+            get_purchase_price(world_price.avg_price, cities_rates),
+        );
+    }
+
+    let world_visits = plan_route(
+        &shopping_list,
+        &unit_costs,
+        home_world.world_id,
+        &transfer_costs,
+    );
+
+    let transfers = world_visits
+        .iter()
+        .filter(|world_visit| world_visit.world_id != home_world.world_id)
+        .count();
+    println!(
+        "Route from {}, {} world transfers:",
+        home_world.name, transfers
+    );
+
+    let mut total_spend = 0.;
+    for (index, world_visit) in world_visits.iter().enumerate() {
+        total_spend += world_visit.spend;
+
+        println!(
+            "{}. {}",
+            index + 1,
+            worlds_names
+                .get(&world_visit.world_id)
+                .map(String::as_str)
+                .unwrap_or("Unknown world")
+        );
+        for purchase in &world_visit.purchases {
+            println!(
+                "   {:<40} x{:<5} {:>10.0} gil each {:>12.0} gil",
+                items_names
+                    .get(&purchase.item_id)
+                    .map(String::as_str)
+                    .unwrap_or_default(),
+                purchase.quantity,
+                purchase.unit_cost,
+                purchase.unit_cost * purchase.quantity as f64
+            );
+        }
+        println!(
+            "   Spent {:.0} gil, {:.0} gil so far",
+            world_visit.spend, total_spend
+        );
+    }
+
+    if transfers > 0 {
+        println!("Return to {}", home_world.name);
+    }
+
+    for item in &shopping_list {
+        let is_routed = world_visits.iter().any(|world_visit| {
+            world_visit
+                .purchases
+                .iter()
+                .any(|purchase| purchase.item_id == item.item_id)
+        });
+
+        if !is_routed {
+            println!(
+                "No prices recorded on a reachable world for {}",
+                items_names
+                    .get(&item.item_id)
+                    .map(String::as_str)
+                    .unwrap_or_default()
+            );
+        }
+    }
+}
+
+/// Parses `item[:quantity]`, the quantity defaults to 1
+fn parse_shopping_list_entry(entry: &str) -> (&str, u64) {
+    match entry.rsplit_once(':') {
+        Some((item, quantity)) => match quantity.trim().parse() {
+            Ok(quantity) => (item.trim(), quantity),
+            Err(_) => (entry.trim(), 1),
+        },
+        None => (entry.trim(), 1),
+    }
+}
+
+async fn report(args: &ReportArgs, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool);
+
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
+        .await
+        .unwrap();
+    let trade_opportunities = get_report(
+        &item_trades,
+        home_world.world_id,
+        &args.report_filter.to_options(Some(args.limit)),
+    )
+    .await
+    .unwrap();
+
+    if trade_opportunities.is_empty() {
+        println!("No trade opportunities for {}", home_world.name);
+        return;
+    }
+
+    print_report(&trade_opportunities);
+}
+
+async fn export(args: &ExportArgs, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool);
+
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
+        .await
+        .unwrap();
+    let trade_opportunities = get_report(
+        &item_trades,
+        home_world.world_id,
+        &args.report_filter.to_options(args.limit),
+    )
+    .await
+    .unwrap();
+
+    match &args.output {
+        Some(output) => {
+            let file = BufWriter::new(File::create(output).unwrap());
+            write_export(&trade_opportunities, args.format, file).unwrap();
+            println!(
+                "Exported {} trade opportunities to {}",
+                trade_opportunities.len(),
+                output.display()
+            );
+        }
+        None => write_export(&trade_opportunities, args.format, io::stdout().lock()).unwrap(),
+    }
+}
+
+async fn export_xlsx(args: &ExportXlsxArgs, config: &Config, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool);
+    let options = args.report_filter.to_options(args.limit);
+
+    let mut home_worlds: Vec<(String, String)> =
+        config.home_worlds_by_data_center().into_iter().collect();
+    home_worlds.sort();
+
+    if home_worlds.is_empty() {
+        println!("No home worlds configured, set HOME_WORLDS");
+        return;
+    }
+
+    let mut sheets = Vec::new();
+
+    for (data_center_name, home_world_name) in home_worlds {
+        let home_world = server_data
+            .get_world_by_name(home_world_name.clone())
+            .await
+            .unwrap();
+        let trade_opportunities = get_report(&item_trades, home_world.world_id, &options)
+            .await
+            .unwrap();
+
+        sheets.push(WorkbookSheet {
+            name: format!("{data_center_name} - {home_world_name}"),
+            trade_opportunities,
+        });
+    }
+
+    let output = args.output.as_ref().unwrap_or(&config.export.xlsx_file);
+    write_workbook(&sheets, output).unwrap();
+
+    println!(
+        "Exported {} home worlds to {}",
+        sheets.len(),
+        output.display()
+    );
 }
 
-async fn sync_trades(args: &SyncTradesArgs, pool: Pool<MySql>) {
+async fn render_html_report(args: &RenderHtmlArgs, config: &Config, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
     let item_trades = ItemTrades::new(pool.clone());
+    let world_price_data = WorldPriceData::new(pool);
+    let options = args.report_filter.to_options(Some(args.limit));
+
+    let mut home_worlds: Vec<(String, String)> =
+        config.home_worlds_by_data_center().into_iter().collect();
+    home_worlds.sort();
+
+    if home_worlds.is_empty() {
+        println!("No home worlds configured, set HOME_WORLDS");
+        return;
+    }
+
+    let mut sections = Vec::new();
+
+    for (data_center_name, home_world_name) in home_worlds {
+        let home_world = server_data
+            .get_world_by_name(home_world_name.clone())
+            .await
+            .unwrap();
+        let trade_opportunities = get_report(&item_trades, home_world.world_id, &options)
+            .await
+            .unwrap();
+
+        let mut price_history: HashMap<u64, Vec<f64>> = HashMap::new();
+        for world_price in world_price_data
+            .get_world_price_history(home_world.world_id, args.days)
+            .await
+            .unwrap()
+        {
+            price_history
+                .entry(world_price.item_id)
+                .or_default()
+                .push(world_price.avg_price);
+        }
+
+        sections.push(HtmlSection {
+            title: format!("{data_center_name} - {home_world_name}"),
+            trade_opportunities,
+            price_history,
+        });
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let generated_at = format!("{} {:02}:{:02} UTC", now.date(), now.hour(), now.minute());
+
+    let output = args.output.as_ref().unwrap_or(&config.export.html_file);
+    fs::write(output, render_html(&sections, &generated_at)).unwrap();
+
+    println!(
+        "Rendered {} home worlds to {}",
+        sections.len(),
+        output.display()
+    );
+}
+
+async fn item(args: &ItemArgs, config: &Config, pool: Pool<MySql>) {
+    let Some(data_center_name) = config.data_center_name(args.data_center_name.as_ref()) else {
+        println!("No data center given or configured");
+        return;
+    };
+    let item_data = ItemData::new(pool.clone());
+    let server_data = ServerData::new(pool);
+
+    let mut matches: Vec<(f64, DBItem)> = item_data
+        .get_all_items()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|item| (get_name_match_score(&args.query, &item.name), item))
+        .collect();
+    matches.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    let Some((_, item)) = matches.first() else {
+        println!("No items synced yet, run sync-base-data first");
+        return;
+    };
+
+    if !item.name.eq_ignore_ascii_case(args.query.trim()) {
+        let alternatives = matches
+            .iter()
+            .skip(1)
+            .take(args.alternatives)
+            .map(|(_, item)| item.name.as_str())
+            .collect::<Vec<_>>();
+
+        if !alternatives.is_empty() {
+            println!("Other matches: {}", alternatives.join(", "));
+        }
+    }
+
+    let server = server_data.get_server(data_center_name).await.unwrap();
+
+    let worlds_markets = try_join_all(server.worlds.iter().map(|world| async {
+        tokio::try_join!(
+            get_item_listings_by_world(vec![item.item_id], world.name.clone()),
+            get_item_sale_history_by_world(vec![item.item_id], world.name.clone(), None),
+        )
+    }))
+    .await
+    .unwrap();
+
+    let now = now_timestamp();
+    let mut worlds_rows = Vec::new();
+
+    for (world, (item_listings, sale_history)) in server.worlds.iter().zip(worlds_markets) {
+        let item_listings = item_listings.items.get(&item.item_id);
+        let avg_price = sale_history
+            .items
+            .get(&item.item_id)
+            .and_then(|item_sale_history| get_price_stats(&item_sale_history.entries))
+            .map(|price_stats| price_stats.avg_price);
+        let cheapest_listing = item_listings.and_then(|item_listings| {
+            item_listings
+                .listings
+                .iter()
+                .map(|listing| listing.price_per_unit)
+                .min()
+        });
+        let velocity = item_listings
+            .map(|item_listings| item_listings.sale_velocity)
+            .unwrap_or_default();
+        let last_upload_time = item_listings
+            .map(|item_listings| item_listings.last_upload_time / 1000)
+            .unwrap_or_default();
+
+        worlds_rows.push((
+            world,
+            avg_price,
+            cheapest_listing,
+            velocity,
+            last_upload_time,
+        ));
+    }
+
+    worlds_rows.sort_by_key(|(_, _, cheapest_listing, _, _)| cheapest_listing.unwrap_or(u64::MAX));
+
+    println!(
+        "{} ({}) on {}",
+        item.name, item.item_id, server.data_center.name
+    );
+    println!(
+        "{:<16} {:>12} {:>16} {:>10} {:>14}",
+        "World", "Avg price", "Cheapest listing", "Per day", "Last update"
+    );
+    for (world, avg_price, cheapest_listing, velocity, last_upload_time) in worlds_rows {
+        let last_update = match last_upload_time {
+            0 => "never".to_string(),
+            _ => format!(
+                "{:.1}h ago",
+                now.saturating_sub(last_upload_time) as f64 / 3600.
+            ),
+        };
+
+        println!(
+            "{:<16} {:>12} {:>16} {:>10.2} {:>14}",
+            world.name,
+            avg_price
+                .map(|avg_price| format!("{avg_price:.0}"))
+                .unwrap_or_else(|| "-".to_string()),
+            cheapest_listing
+                .map(|cheapest_listing| cheapest_listing.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            velocity,
+            last_update
+        );
+    }
+}
+
+async fn tui(args: &TuiArgs, pool: Pool<MySql>) {
     let server_data = ServerData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool.clone());
+    let sync_run_data = SyncRunData::new(pool.clone());
+    let world_price_data = WorldPriceData::new(pool.clone());
+    let watchlist_data = WatchlistData::new(pool);
+
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
+        .await
+        .unwrap();
+    let trade_opportunities = get_report(
+        &item_trades,
+        home_world.world_id,
+        &args.report_filter.to_options(None),
+    )
+    .await
+    .unwrap();
+
+    let worlds_names: HashMap<u64, String> = server_data
+        .get_data_center_worlds(home_world.data_center_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|world| (world.world_id, world.name))
+        .collect();
+
+    let mut world_prices: HashMap<u64, Vec<(String, f64)>> = HashMap::new();
+    if let Some(sync_run_id) = sync_run_data
+        .get_latest_finished_sync_run_id(home_world.world_id)
+        .await
+        .unwrap()
+    {
+        for world_price in world_price_data
+            .get_world_prices(sync_run_id)
+            .await
+            .unwrap()
+        {
+            if let Some(world_name) = worlds_names.get(&world_price.world_id) {
+                world_prices
+                    .entry(world_price.item_id)
+                    .or_default()
+                    .push((world_name.clone(), world_price.avg_price));
+            }
+        }
+    }
+
+    let mut price_history: HashMap<u64, Vec<f64>> = HashMap::new();
+    for world_price in world_price_data
+        .get_world_price_history(home_world.world_id, args.history_days)
+        .await
+        .unwrap()
+    {
+        price_history
+            .entry(world_price.item_id)
+            .or_default()
+            .push(world_price.avg_price);
+    }
+
+    let data = TuiData {
+        home_world_name: home_world.name,
+        trade_opportunities,
+        world_prices,
+        price_history,
+    };
+    let options = TuiOptions {
+        sort: args.report_filter.sort,
+        ascending: args.report_filter.ascending,
+        watchlist: args.watchlist.clone(),
+        shopping_list: args.shopping_list.clone(),
+        days: args.days,
+    };
+
+    run_tui(data, &options, &watchlist_data).await.unwrap();
+}
+
+async fn chart(args: &ChartArgs, config: &Config, pool: Pool<MySql>) {
+    let Some(data_center_name) = config.data_center_name(args.data_center_name.as_ref()) else {
+        println!("No data center given or configured");
+        return;
+    };
     let item_data = ItemData::new(pool.clone());
-    let market_imports = MarketImport::new(item_trades, server_data, item_data);
+    let server_data = ServerData::new(pool.clone());
+    let world_price_data = WorldPriceData::new(pool);
+
+    let item = item_data.get_item(&args.item).await.unwrap();
+    let server = server_data.get_server(data_center_name).await.unwrap();
+
+    let worlds: Vec<_> = server
+        .worlds
+        .into_iter()
+        .filter(|world| {
+            args.worlds.is_empty()
+                || args
+                    .worlds
+                    .iter()
+                    .any(|world_name| world_name.eq_ignore_ascii_case(&world.name))
+        })
+        .collect();
+
+    let sale_histories = try_join_all(worlds.iter().map(|world| {
+        get_item_sale_history_by_world(
+            vec![item.item_id],
+            world.name.clone(),
+            Some(args.days as u64 * 24 * 60 * 60),
+        )
+    }))
+    .await
+    .unwrap();
+
+    let mut synced_prices: HashMap<u64, Vec<(u64, f64)>> = HashMap::new();
+    for world_price in world_price_data
+        .get_item_price_history(item.item_id, args.days)
+        .await
+        .unwrap()
+    {
+        synced_prices
+            .entry(world_price.world_id)
+            .or_default()
+            .push((
+                world_price.recorded_at.assume_utc().unix_timestamp() as u64,
+                world_price.avg_price,
+            ));
+    }
+
+    let series: Vec<ChartSeries> = worlds
+        .iter()
+        .zip(sale_histories)
+        .map(|(world, sale_history)| ChartSeries {
+            world_name: world.name.clone(),
+            daily_sales: sale_history
+                .items
+                .get(&item.item_id)
+                .map(|item_sale_history| get_daily_sale_history(&item_sale_history.entries))
+                .unwrap_or_default(),
+            synced_prices: synced_prices.remove(&world.world_id).unwrap_or_default(),
+        })
+        .collect();
+
+    let format = args
+        .format
+        .unwrap_or_else(|| ChartFormat::from_path(&args.output));
+    let title = format!(
+        "{} on {}, last {} days",
+        item.name, server.data_center.name, args.days
+    );
+
+    write_chart(&args.output, format, &title, &series).unwrap();
+
+    println!(
+        "Charted {} worlds to {}",
+        series.len(),
+        args.output.display()
+    );
+}
+
+async fn sync_runs(args: &SyncRunsArgs, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let sync_run_data = SyncRunData::new(pool);
+
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
+        .await
+        .unwrap();
+    let sync_runs = sync_run_data
+        .get_sync_runs(home_world.world_id, args.limit)
+        .await
+        .unwrap();
+
+    println!(
+        "{:>8} {:<10} {:<20} {:<20} {:>8} {:>9}",
+        "Run", "Status", "Started", "Finished", "Items", "Rejected"
+    );
+    for sync_run in sync_runs {
+        println!(
+            "{:>8} {:<10} {:<20} {:<20} {:>8} {:>9}",
+            sync_run.id,
+            sync_run.status,
+            format_datetime(sync_run.started_at),
+            sync_run
+                .finished_at
+                .map(format_datetime)
+                .unwrap_or_default(),
+            sync_run.items_count,
+            sync_run.rejected_items_count
+        );
+    }
+}
+
+async fn diff(args: &DiffArgs, pool: Pool<MySql>) {
+    let server_data = ServerData::new(pool.clone());
+    let item_trades = ItemTrades::new(pool.clone());
+    let sync_run_data = SyncRunData::new(pool);
 
-    println!("Importing trades data ...");
-    market_imports
-        .import_market_trade_volumes(args.data_center_name.clone(), args.home_world_name.clone())
+    let home_world = server_data
+        .get_world_by_name(args.home_world_name.clone())
         .await
         .unwrap();
-    println!("Done!");
 
-    println!("Trades data successfully synced!");
+    let to = match args.to {
+        Some(to) => Some(to),
+        None => sync_run_data
+            .get_latest_finished_sync_run_id(home_world.world_id)
+            .await
+            .unwrap(),
+    };
+    let Some(to) = to else {
+        println!("No finished sync run for {}", home_world.name);
+        return;
+    };
+
+    let from = match args.from {
+        Some(from) => Some(from),
+        None => sync_run_data
+            .get_previous_finished_sync_run_id(home_world.world_id, to)
+            .await
+            .unwrap(),
+    };
+    let Some(from) = from else {
+        println!("No finished sync run for {} before #{to}", home_world.name);
+        return;
+    };
+
+    let from_sync_run = sync_run_data.get_sync_run(from).await.unwrap();
+    let to_sync_run = sync_run_data.get_sync_run(to).await.unwrap();
+
+    for sync_run in [&from_sync_run, &to_sync_run] {
+        if sync_run.world_id != home_world.world_id {
+            println!(
+                "Sync run #{} is not a {} sync run",
+                sync_run.id, home_world.name
+            );
+            return;
+        }
+    }
+
+    let sync_run_diff = diff_sync_runs(
+        item_trades
+            .get_sync_run_trade_opportunities(from)
+            .await
+            .unwrap(),
+        item_trades
+            .get_sync_run_trade_opportunities(to)
+            .await
+            .unwrap(),
+        &args.report_filter.to_options(Some(args.limit)),
+        args.changes,
+    );
+
+    println!(
+        "{}: sync run #{} ({}) -> #{} ({})",
+        home_world.name,
+        from_sync_run.id,
+        format_datetime(from_sync_run.started_at),
+        to_sync_run.id,
+        format_datetime(to_sync_run.started_at)
+    );
+    print_diff(&sync_run_diff);
+}
+
+fn format_datetime(datetime: PrimitiveDateTime) -> String {
+    format!(
+        "{} {:02}:{:02}",
+        datetime.date(),
+        datetime.hour(),
+        datetime.minute()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SyncConfig;
+
+    fn config() -> Config {
+        Config {
+            home_worlds: vec!["Light:Odin".to_string()],
+            sync: SyncConfig {
+                exclude_stale: true,
+                ..SyncConfig::default()
+            },
+            thresholds: ThresholdsConfig {
+                min_velocity: 2.,
+                min_sample_size: 5,
+                ..ThresholdsConfig::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn arguments_replace_the_config() {
+        let cli = Cli::try_parse_from([
+            "agregator",
+            "--home-worlds",
+            "Light:Lich,Chaos:Omega",
+            "--max-requests-per-second",
+            "7",
+            "sync-trades",
+            "Light",
+            "--min-velocity",
+            "3.5",
+            "--include-stale",
+        ])
+        .unwrap();
+        let mut config = config();
+
+        cli.config_overrides.apply(&mut config);
+        let Commands::SyncTrades(args) = &cli.command else {
+            panic!("expected sync-trades");
+        };
+        let options = args.trade_import.to_options(&config);
+
+        assert_eq!(config.home_worlds, vec!["Light:Lich", "Chaos:Omega"]);
+        assert_eq!(config.sync.max_requests_per_second, 7.);
+        assert_eq!(options.liquidity_thresholds.min_velocity, 3.5);
+        assert!(!options.exclude_stale);
+        // Values without an argument are kept
+        assert_eq!(options.liquidity_thresholds.min_sample_size, 5);
+        assert_eq!(options.max_data_age, 48 * 60 * 60);
+    }
+
+    #[test]
+    fn config_is_kept_without_arguments() {
+        let cli = Cli::try_parse_from(["agregator", "sync-trades"]).unwrap();
+        let mut config = config();
+
+        cli.config_overrides.apply(&mut config);
+        let Commands::SyncTrades(args) = &cli.command else {
+            panic!("expected sync-trades");
+        };
+        let options = args.trade_import.to_options(&config);
+
+        assert_eq!(config.home_worlds, vec!["Light:Odin"]);
+        assert_eq!(options.liquidity_thresholds.min_velocity, 2.);
+        assert!(options.exclude_stale);
+    }
 }
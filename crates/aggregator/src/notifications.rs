@@ -0,0 +1,46 @@
+use crate::config::NotificationsConfig;
+use reqwest::{Client, Response};
+use serde::Serialize;
+use tracing::warn;
+
+/// Body accepted by both Discord (`content`) and Slack (`text`) webhooks
+#[derive(Serialize)]
+struct WebhookMessage<'a> {
+    content: &'a str,
+    text: &'a str,
+}
+
+/// Posts messages to the configured webhooks. Failures are logged and don't stop the command
+pub struct Notifier {
+    client: Client,
+    webhook_urls: Vec<String>,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig) -> Self {
+        Self {
+            client: Client::new(),
+            webhook_urls: config.webhook_urls.clone(),
+        }
+    }
+
+    pub async fn notify(&self, message: &str) {
+        for webhook_url in &self.webhook_urls {
+            let result = self
+                .client
+                .post(webhook_url)
+                .json(&WebhookMessage {
+                    content: message,
+                    text: message,
+                })
+                .send()
+                .await
+                .and_then(Response::error_for_status);
+
+            // The url holds the webhook token
+            if let Err(error) = result {
+                warn!(error = %error.without_url(), "Notification failed");
+            }
+        }
+    }
+}
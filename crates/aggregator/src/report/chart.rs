@@ -0,0 +1,191 @@
+use super::Error;
+use crate::analysis::{DailySales, SECONDS_IN_DAY};
+use clap::ValueEnum;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use sqlx::types::time::OffsetDateTime;
+use std::path::Path;
+
+const CHART_SIZE: (u32, u32) = (1200, 900);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    /// Format matching the file extension, svg when it is neither svg nor png
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("png") => ChartFormat::Png,
+            _ => ChartFormat::Svg,
+        }
+    }
+}
+
+pub struct ChartSeries {
+    pub world_name: String,
+    /// Sales from the Universalis history, the oldest day first
+    pub daily_sales: Vec<DailySales>,
+    /// Unix timestamps and average prices recorded by the sync runs
+    pub synced_prices: Vec<(u64, f64)>,
+}
+
+/// Price chart above a units sold per day chart, a line per world. Prices recorded by the
+/// sync runs are drawn as dots over the sale history line of their world
+pub fn write_chart(
+    path: &Path,
+    format: ChartFormat,
+    title: &str,
+    series: &[ChartSeries],
+) -> Result<(), Error> {
+    match format {
+        ChartFormat::Svg => draw_chart(
+            SVGBackend::new(path, CHART_SIZE).into_drawing_area(),
+            title,
+            series,
+        ),
+        ChartFormat::Png => draw_chart(
+            BitMapBackend::new(path, CHART_SIZE).into_drawing_area(),
+            title,
+            series,
+        ),
+    }
+}
+
+fn draw_chart<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    series: &[ChartSeries],
+) -> Result<(), Error> {
+    root.fill(&WHITE).map_err(chart_error)?;
+    let root = root
+        .titled(title, ("sans-serif", 28))
+        .map_err(chart_error)?;
+    let areas = root.split_evenly((2, 1));
+
+    let timestamps = series.iter().flat_map(|world_series| {
+        world_series
+            .daily_sales
+            .iter()
+            .map(|daily_sales| daily_sales.day as f64)
+            .chain(
+                world_series
+                    .synced_prices
+                    .iter()
+                    .map(|(timestamp, _)| *timestamp as f64),
+            )
+    });
+    let (start, end) = timestamps.fold(
+        (f64::INFINITY, f64::NEG_INFINITY),
+        |(start, end), timestamp| (start.min(timestamp), end.max(timestamp)),
+    );
+
+    if start > end {
+        root.present().map_err(chart_error)?;
+        return Ok(());
+    }
+
+    let end = end.max(start + SECONDS_IN_DAY as f64);
+    let max_price = series
+        .iter()
+        .flat_map(|world_series| {
+            world_series
+                .daily_sales
+                .iter()
+                .map(|daily_sales| daily_sales.avg_price)
+                .chain(world_series.synced_prices.iter().map(|(_, price)| *price))
+        })
+        .fold(1., f64::max);
+    let max_quantity = series
+        .iter()
+        .flat_map(|world_series| world_series.daily_sales.iter())
+        .map(|daily_sales| daily_sales.quantity as f64)
+        .fold(1., f64::max);
+
+    let mut price_chart = ChartBuilder::on(&areas[0])
+        .caption("Average price", ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(70)
+        .build_cartesian_2d(start..end, 0.0..max_price * 1.1)
+        .map_err(chart_error)?;
+    price_chart
+        .configure_mesh()
+        .x_label_formatter(&format_date)
+        .y_label_formatter(&|price| format!("{price:.0}"))
+        .y_desc("Gil")
+        .draw()
+        .map_err(chart_error)?;
+
+    let mut velocity_chart = ChartBuilder::on(&areas[1])
+        .caption("Units sold per day", ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(70)
+        .build_cartesian_2d(start..end, 0.0..max_quantity * 1.1)
+        .map_err(chart_error)?;
+    velocity_chart
+        .configure_mesh()
+        .x_label_formatter(&format_date)
+        .y_label_formatter(&|quantity| format!("{quantity:.0}"))
+        .y_desc("Units")
+        .draw()
+        .map_err(chart_error)?;
+
+    for (index, world_series) in series.iter().enumerate() {
+        let color = Palette99::pick(index).to_rgba();
+
+        price_chart
+            .draw_series(LineSeries::new(
+                world_series
+                    .daily_sales
+                    .iter()
+                    .map(|daily_sales| (daily_sales.day as f64, daily_sales.avg_price)),
+                color.stroke_width(2),
+            ))
+            .map_err(chart_error)?
+            .label(world_series.world_name.as_str())
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+            });
+        price_chart
+            .draw_series(world_series.synced_prices.iter().map(|(timestamp, price)| {
+                Circle::new((*timestamp as f64, *price), 3, color.filled())
+            }))
+            .map_err(chart_error)?;
+
+        velocity_chart
+            .draw_series(LineSeries::new(
+                world_series
+                    .daily_sales
+                    .iter()
+                    .map(|daily_sales| (daily_sales.day as f64, daily_sales.quantity as f64)),
+                color.stroke_width(2),
+            ))
+            .map_err(chart_error)?;
+    }
+
+    price_chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(chart_error)?;
+
+    root.present().map_err(chart_error)?;
+
+    Ok(())
+}
+
+fn format_date(timestamp: &f64) -> String {
+    match OffsetDateTime::from_unix_timestamp(*timestamp as i64) {
+        Ok(date) => format!("{:02}-{:02}", u8::from(date.month()), date.day()),
+        Err(_) => String::new(),
+    }
+}
+
+fn chart_error<E: std::error::Error>(error: E) -> Error {
+    Error::Chart(error.to_string())
+}
@@ -0,0 +1,361 @@
+use super::{apply_report_options, ReportOptions};
+use crate::db::DBTradeOpportunity;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+pub struct ValueChange {
+    pub item_name: String,
+    pub from: f64,
+    pub to: f64,
+}
+
+impl ValueChange {
+    pub fn relative_change(&self) -> f64 {
+        (self.to - self.from) / self.from.abs().max(1.)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheapestWorldChange {
+    pub item_name: String,
+    pub from_world_name: String,
+    pub from_unit_cost: f64,
+    pub to_world_name: String,
+    pub to_unit_cost: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncRunDiff {
+    /// Top trade opportunities of the newer run that were not in the top of the older one
+    pub entered: Vec<DBTradeOpportunity>,
+    /// Top trade opportunities of the older run that dropped out of the top, with their rank
+    /// in the newer run when it still found them
+    pub left: Vec<(DBTradeOpportunity, Option<usize>)>,
+    pub cheapest_world_changes: Vec<CheapestWorldChange>,
+    /// Home world price changes, the largest relative change first
+    pub price_changes: Vec<ValueChange>,
+    /// Forecast velocity changes, the largest relative change first
+    pub velocity_changes: Vec<ValueChange>,
+}
+
+/// Compares the trade opportunities of two sync runs. The top is the first `options.limit`
+/// trade opportunities of each run, changes are listed for items both runs found and cut to
+/// `changes_limit`
+pub fn diff_sync_runs(
+    from: Vec<DBTradeOpportunity>,
+    to: Vec<DBTradeOpportunity>,
+    options: &ReportOptions,
+    changes_limit: usize,
+) -> SyncRunDiff {
+    let ranking_options = ReportOptions {
+        limit: None,
+        ..options.clone()
+    };
+    let from = apply_report_options(from, &ranking_options);
+    let to = apply_report_options(to, &ranking_options);
+
+    let top = options.limit.unwrap_or(usize::MAX);
+    let from_top: HashSet<u64> = from
+        .iter()
+        .take(top)
+        .map(|trade_opportunity| trade_opportunity.item_id)
+        .collect();
+    let to_top: HashSet<u64> = to
+        .iter()
+        .take(top)
+        .map(|trade_opportunity| trade_opportunity.item_id)
+        .collect();
+
+    let entered = to
+        .iter()
+        .take(top)
+        .filter(|trade_opportunity| !from_top.contains(&trade_opportunity.item_id))
+        .cloned()
+        .collect();
+    let left = from
+        .iter()
+        .take(top)
+        .filter(|trade_opportunity| !to_top.contains(&trade_opportunity.item_id))
+        .map(|trade_opportunity| {
+            let rank = to
+                .iter()
+                .position(|to_trade_opportunity| {
+                    to_trade_opportunity.item_id == trade_opportunity.item_id
+                })
+                .map(|index| index + 1);
+
+            (trade_opportunity.clone(), rank)
+        })
+        .collect();
+
+    let from: HashMap<u64, &DBTradeOpportunity> = from
+        .iter()
+        .map(|trade_opportunity| (trade_opportunity.item_id, trade_opportunity))
+        .collect();
+
+    let mut cheapest_world_changes = Vec::new();
+    let mut price_changes = Vec::new();
+    let mut velocity_changes = Vec::new();
+
+    for to_trade_opportunity in &to {
+        let Some(from_trade_opportunity) = from.get(&to_trade_opportunity.item_id) else {
+            continue;
+        };
+
+        if from_trade_opportunity.cheapest_world_id != to_trade_opportunity.cheapest_world_id {
+            cheapest_world_changes.push(CheapestWorldChange {
+                item_name: to_trade_opportunity.item_name.clone(),
+                from_world_name: from_trade_opportunity.cheapest_world_name.clone(),
+                from_unit_cost: from_trade_opportunity.unit_cost(),
+                to_world_name: to_trade_opportunity.cheapest_world_name.clone(),
+                to_unit_cost: to_trade_opportunity.unit_cost(),
+            });
+        }
+
+        price_changes.push(ValueChange {
+            item_name: to_trade_opportunity.item_name.clone(),
+            from: from_trade_opportunity.home_world_avg_price,
+            to: to_trade_opportunity.home_world_avg_price,
+        });
+        velocity_changes.push(ValueChange {
+            item_name: to_trade_opportunity.item_name.clone(),
+            from: from_trade_opportunity.forecast_velocity,
+            to: to_trade_opportunity.forecast_velocity,
+        });
+    }
+
+    cheapest_world_changes.truncate(changes_limit);
+
+    SyncRunDiff {
+        entered,
+        left,
+        cheapest_world_changes,
+        price_changes: largest_changes(price_changes, changes_limit),
+        velocity_changes: largest_changes(velocity_changes, changes_limit),
+    }
+}
+
+fn largest_changes(changes: Vec<ValueChange>, limit: usize) -> Vec<ValueChange> {
+    let mut changes: Vec<ValueChange> = changes
+        .into_iter()
+        .filter(|change| change.relative_change() != 0.)
+        .collect();
+
+    changes.sort_by(|a, b| {
+        b.relative_change()
+            .abs()
+            .total_cmp(&a.relative_change().abs())
+    });
+    changes.truncate(limit);
+
+    changes
+}
+
+pub fn print_diff(diff: &SyncRunDiff) {
+    println!("Entered the top:");
+    for trade_opportunity in &diff.entered {
+        println!(
+            "  {:<40} {:<14} {:>12.0} gil per day",
+            trade_opportunity.item_name,
+            trade_opportunity.cheapest_world_name,
+            trade_opportunity.expected_profit
+        );
+    }
+
+    println!("Left the top:");
+    for (trade_opportunity, rank) in &diff.left {
+        println!(
+            "  {:<40} {:<14} {:>12.0} gil per day, {}",
+            trade_opportunity.item_name,
+            trade_opportunity.cheapest_world_name,
+            trade_opportunity.expected_profit,
+            rank.map(|rank| format!("now #{rank}"))
+                .unwrap_or_else(|| "no longer found".to_string())
+        );
+    }
+
+    println!("Cheapest world changed:");
+    for change in &diff.cheapest_world_changes {
+        println!(
+            "  {:<40} {} ({:.0}) -> {} ({:.0})",
+            change.item_name,
+            change.from_world_name,
+            change.from_unit_cost,
+            change.to_world_name,
+            change.to_unit_cost
+        );
+    }
+
+    println!("Largest home world price changes:");
+    print_value_changes(&diff.price_changes, 0);
+
+    println!("Largest velocity changes:");
+    print_value_changes(&diff.velocity_changes, 2);
+}
+
+fn print_value_changes(changes: &[ValueChange], precision: usize) {
+    for change in changes {
+        println!(
+            "  {:<40} {:>10.precision$} -> {:>10.precision$} {:>+7.1}%",
+            change.item_name,
+            change.from,
+            change.to,
+            change.relative_change() * 100.
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ReportSort;
+
+    fn trade_opportunity(item_id: u64, expected_profit: f64) -> DBTradeOpportunity {
+        DBTradeOpportunity {
+            item_id,
+            item_name: format!("Item {item_id}"),
+            search_category_id: None,
+            ui_category_id: None,
+            stack_size: 99,
+            home_world_id: 1,
+            home_world_name: "Odin".to_string(),
+            cheapest_world_id: 2,
+            cheapest_world_name: "Lich".to_string(),
+            home_world_avg_price: 1000.,
+            cheapest_world_avg_price: 500.,
+            price_diff_score: 2.,
+            unit_profit: 450.,
+            sale_score: 1.,
+            forecast_velocity: 1.,
+            expected_profit,
+            competing_retainers: 0,
+            days_of_supply: 0.,
+            lowest_listing_changes: 0,
+            saturation_score: 0.,
+            price_std_dev: 0.,
+            price_cv: 0.,
+            sample_count: 10,
+            confidence_score: 0.5,
+            manipulation_flags: String::new(),
+            data_age: 0,
+            is_stale: false,
+        }
+    }
+
+    fn options(limit: usize) -> ReportOptions {
+        ReportOptions {
+            sort: ReportSort::ExpectedProfit,
+            ascending: false,
+            min_profit: None,
+            min_velocity: None,
+            search_category_ids: Vec::new(),
+            ui_category_ids: Vec::new(),
+            cheapest_world_name: None,
+            include_suspicious: false,
+            include_stale: false,
+            limit: Some(limit),
+        }
+    }
+
+    fn items_ids(trade_opportunities: &[DBTradeOpportunity]) -> Vec<u64> {
+        trade_opportunities
+            .iter()
+            .map(|trade_opportunity| trade_opportunity.item_id)
+            .collect()
+    }
+
+    #[test]
+    fn lists_items_entering_and_leaving_the_top() {
+        let from = vec![
+            trade_opportunity(1, 300.),
+            trade_opportunity(2, 200.),
+            trade_opportunity(3, 100.),
+            trade_opportunity(5, 150.),
+        ];
+        let to = vec![
+            trade_opportunity(1, 300.),
+            trade_opportunity(2, 50.),
+            trade_opportunity(3, 250.),
+            trade_opportunity(4, 400.),
+        ];
+
+        let diff = diff_sync_runs(from, to, &options(3), 10);
+
+        assert_eq!(items_ids(&diff.entered), vec![4, 3]);
+        assert_eq!(
+            diff.left
+                .iter()
+                .map(|(trade_opportunity, rank)| (trade_opportunity.item_id, *rank))
+                .collect::<Vec<_>>(),
+            vec![(2, Some(4)), (5, None)]
+        );
+    }
+
+    #[test]
+    fn lists_changes_of_items_both_runs_found() {
+        let mut moved = trade_opportunity(1, 100.);
+        moved.cheapest_world_id = 3;
+        moved.cheapest_world_name = "Phoenix".to_string();
+        moved.unit_profit = 400.;
+        let mut pricier = trade_opportunity(2, 100.);
+        pricier.home_world_avg_price = 1500.;
+        let mut slower = trade_opportunity(3, 100.);
+        slower.home_world_avg_price = 1100.;
+        slower.forecast_velocity = 0.5;
+
+        let from = vec![
+            trade_opportunity(1, 100.),
+            trade_opportunity(2, 100.),
+            trade_opportunity(3, 100.),
+        ];
+        let to = vec![moved, pricier, slower, trade_opportunity(4, 100.)];
+
+        let diff = diff_sync_runs(from, to, &options(10), 10);
+
+        assert_eq!(diff.cheapest_world_changes.len(), 1);
+        let change = &diff.cheapest_world_changes[0];
+        assert_eq!(change.from_world_name, "Lich");
+        assert_eq!(change.to_world_name, "Phoenix");
+        assert_eq!(change.from_unit_cost, 550.);
+        assert_eq!(change.to_unit_cost, 600.);
+
+        // Unchanged prices are left out, the largest relative change comes first
+        assert_eq!(
+            diff.price_changes
+                .iter()
+                .map(|change| (change.item_name.as_str(), change.relative_change()))
+                .collect::<Vec<_>>(),
+            vec![("Item 2", 0.5), ("Item 3", 0.1)]
+        );
+        assert_eq!(diff.velocity_changes.len(), 1);
+        assert_eq!(diff.velocity_changes[0].item_name, "Item 3");
+        assert_eq!(diff.velocity_changes[0].relative_change(), -0.5);
+    }
+
+    #[test]
+    fn cuts_the_changes_to_the_limit() {
+        let from: Vec<DBTradeOpportunity> = (1..=5)
+            .map(|item_id| trade_opportunity(item_id, 100.))
+            .collect();
+        let to = from
+            .iter()
+            .cloned()
+            .map(|mut trade_opportunity| {
+                trade_opportunity.home_world_avg_price *= 1. + trade_opportunity.item_id as f64;
+                trade_opportunity
+            })
+            .collect();
+
+        let diff = diff_sync_runs(from, to, &options(10), 2);
+
+        assert!(diff.entered.is_empty());
+        assert!(diff.left.is_empty());
+        assert_eq!(
+            diff.price_changes
+                .iter()
+                .map(|change| change.item_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Item 5", "Item 4"]
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Error while executing sql")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("Error while writing the export")]
+    Io(#[from] std::io::Error),
+    #[error("Error while writing json")]
+    Json(#[from] serde_json::Error),
+    #[error("Error while writing csv")]
+    Csv(#[from] csv::Error),
+    #[error("Error while writing xlsx")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+    #[error("Error while drawing the chart: {0}")]
+    Chart(String),
+}
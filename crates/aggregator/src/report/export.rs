@@ -0,0 +1,41 @@
+use crate::db::DBTradeOpportunity;
+use crate::report::errors::Error;
+use clap::ValueEnum;
+use std::io::Write;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Ndjson,
+}
+
+pub fn write_export(
+    trade_opportunities: &[DBTradeOpportunity],
+    format: ExportFormat,
+    mut writer: impl Write,
+) -> Result<(), Error> {
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, trade_opportunities)?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(writer);
+
+            for trade_opportunity in trade_opportunities {
+                csv_writer.serialize(trade_opportunity)?;
+            }
+
+            csv_writer.flush()?;
+        }
+        ExportFormat::Ndjson => {
+            for trade_opportunity in trade_opportunities {
+                serde_json::to_writer(&mut writer, trade_opportunity)?;
+                writeln!(writer)?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use crate::db::DBTradeOpportunity;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const UNIVERSALIS_MARKET_URL: &str = "https://universalis.app/market";
+const SPARKLINE_WIDTH: f64 = 100.;
+const SPARKLINE_HEIGHT: f64 = 24.;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:3em}\
+th,td{padding:4px 8px;border-bottom:1px solid #ddd;white-space:nowrap}\
+th{cursor:pointer;background:#f4f4f4;position:sticky;top:0}\
+td.number{text-align:right;font-variant-numeric:tabular-nums}\
+polyline{fill:none;stroke:#2a6fdb;stroke-width:1.5}";
+
+/// Sorts a table by the clicked column, numbers by their `data-value`, again for descending order
+const SCRIPT: &str = "document.querySelectorAll('th').forEach(function(th){\
+th.addEventListener('click',function(){\
+var table=th.closest('table'),body=table.tBodies[0],index=th.cellIndex;\
+var ascending=th.dataset.order!=='asc';th.dataset.order=ascending?'asc':'desc';\
+var value=function(row){var cell=row.cells[index];\
+return cell.dataset.value!==undefined?parseFloat(cell.dataset.value):cell.textContent;};\
+Array.from(body.rows).sort(function(a,b){var x=value(a),y=value(b);\
+var order=x<y?-1:x>y?1:0;return ascending?order:-order;})\
+.forEach(function(row){body.appendChild(row);});});});";
+
+pub struct HtmlSection {
+    pub title: String,
+    pub trade_opportunities: Vec<DBTradeOpportunity>,
+    /// Home world average prices per item, the oldest first
+    pub price_history: HashMap<u64, Vec<f64>>,
+}
+
+/// Self-contained page with a sortable table per section
+pub fn render_html(sections: &[HtmlSection], generated_at: &str) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Market board trade opportunities</title>\n");
+    let _ = writeln!(html, "<style>{STYLE}</style>\n</head>\n<body>");
+    html.push_str("<h1>Market board trade opportunities</h1>\n");
+    let _ = writeln!(html, "<p>Generated {}</p>", escape_html(generated_at));
+
+    for section in sections {
+        render_section(&mut html, section);
+    }
+
+    let _ = writeln!(html, "<script>{SCRIPT}</script>\n</body>\n</html>");
+
+    html
+}
+
+fn render_section(html: &mut String, section: &HtmlSection) {
+    let _ = writeln!(html, "<h2>{}</h2>", escape_html(&section.title));
+
+    if section.trade_opportunities.is_empty() {
+        html.push_str("<p>No trade opportunities</p>\n");
+        return;
+    }
+
+    html.push_str("<table>\n<thead><tr>");
+    for header in [
+        "Item",
+        "Price history",
+        "Buy on",
+        "Buy",
+        "Sell",
+        "Profit",
+        "Per day",
+        "Gil per day",
+        "Supply",
+        "Confidence",
+    ] {
+        let _ = write!(html, "<th>{header}</th>");
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+
+    for trade_opportunity in &section.trade_opportunities {
+        let prices = section
+            .price_history
+            .get(&trade_opportunity.item_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let _ = write!(
+            html,
+            "<tr><td><a href=\"{}/{}\">{}</a></td><td>{}</td><td>{}</td>",
+            UNIVERSALIS_MARKET_URL,
+            trade_opportunity.item_id,
+            escape_html(&trade_opportunity.item_name),
+            sparkline(prices),
+            escape_html(&trade_opportunity.cheapest_world_name)
+        );
+        for (value, precision) in [
+            (trade_opportunity.unit_cost(), 0),
+            (trade_opportunity.home_world_avg_price, 0),
+            (trade_opportunity.unit_profit, 0),
+            (trade_opportunity.forecast_velocity, 2),
+            (trade_opportunity.expected_profit, 0),
+            (trade_opportunity.days_of_supply, 1),
+            (trade_opportunity.confidence_score, 2),
+        ] {
+            let _ = write!(
+                html,
+                "<td class=\"number\" data-value=\"{value}\">{value:.precision$}</td>"
+            );
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Inline svg line of the prices scaled between their minimum and maximum
+fn sparkline(prices: &[f64]) -> String {
+    if prices.len() < 2 {
+        return String::new();
+    }
+
+    let min = prices.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = prices.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1. };
+    let step = SPARKLINE_WIDTH / (prices.len() - 1) as f64;
+
+    let points = prices
+        .iter()
+        .enumerate()
+        .map(|(index, price)| {
+            let y = SPARKLINE_HEIGHT - (price - min) / range * SPARKLINE_HEIGHT;
+            format!("{:.1},{:.1}", index as f64 * step, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "<svg width=\"{SPARKLINE_WIDTH}\" height=\"{SPARKLINE_HEIGHT}\" viewBox=\"-1 -1 {} {}\"><polyline points=\"{points}\"/></svg>",
+        SPARKLINE_WIDTH + 2.,
+        SPARKLINE_HEIGHT + 2.
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
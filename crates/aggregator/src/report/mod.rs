@@ -0,0 +1,173 @@
+mod chart;
+mod diff;
+mod errors;
+mod export;
+mod html;
+mod xlsx;
+
+pub(crate) use chart::*;
+pub(crate) use diff::*;
+pub(crate) use errors::*;
+pub(crate) use export::*;
+pub(crate) use html::*;
+pub(crate) use xlsx::*;
+
+use crate::db::{DBTradeOpportunity, ItemTrades};
+use clap::ValueEnum;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ReportSort {
+    ExpectedProfit,
+    UnitProfit,
+    Velocity,
+    PriceDiff,
+    HomePrice,
+    CheapestPrice,
+    Confidence,
+    Saturation,
+    DaysOfSupply,
+    CompetingRetainers,
+}
+
+impl ReportSort {
+    pub(crate) fn value(&self, trade_opportunity: &DBTradeOpportunity) -> f64 {
+        match self {
+            ReportSort::ExpectedProfit => trade_opportunity.expected_profit,
+            ReportSort::UnitProfit => trade_opportunity.unit_profit,
+            ReportSort::Velocity => trade_opportunity.forecast_velocity,
+            ReportSort::PriceDiff => trade_opportunity.price_diff_score,
+            ReportSort::HomePrice => trade_opportunity.home_world_avg_price,
+            ReportSort::CheapestPrice => trade_opportunity.cheapest_world_avg_price,
+            ReportSort::Confidence => trade_opportunity.confidence_score,
+            ReportSort::Saturation => trade_opportunity.saturation_score,
+            ReportSort::DaysOfSupply => trade_opportunity.days_of_supply,
+            ReportSort::CompetingRetainers => trade_opportunity.competing_retainers as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub sort: ReportSort,
+    pub ascending: bool,
+    /// Minimum expected gil per day
+    pub min_profit: Option<f64>,
+    /// Minimum forecast home world sales per day
+    pub min_velocity: Option<f64>,
+    pub search_category_ids: Vec<u64>,
+    pub ui_category_ids: Vec<u64>,
+    pub cheapest_world_name: Option<String>,
+    pub include_suspicious: bool,
+    pub include_stale: bool,
+    pub limit: Option<usize>,
+}
+
+/// Trade opportunities of the latest finished sync run of the home world, filtered and sorted
+pub async fn get_report(
+    item_trades: &ItemTrades,
+    home_world_id: u64,
+    options: &ReportOptions,
+) -> Result<Vec<DBTradeOpportunity>, Error> {
+    let trade_opportunities = item_trades.get_trade_opportunities(home_world_id).await?;
+
+    Ok(apply_report_options(trade_opportunities, options))
+}
+
+pub fn apply_report_options(
+    trade_opportunities: Vec<DBTradeOpportunity>,
+    options: &ReportOptions,
+) -> Vec<DBTradeOpportunity> {
+    let mut trade_opportunities: Vec<DBTradeOpportunity> = trade_opportunities
+        .into_iter()
+        .filter(|trade_opportunity| options.matches(trade_opportunity))
+        .collect();
+
+    trade_opportunities.sort_by(|a, b| {
+        let ordering = options.sort.value(a).total_cmp(&options.sort.value(b));
+
+        if options.ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+
+    if let Some(limit) = options.limit {
+        trade_opportunities.truncate(limit);
+    }
+
+    trade_opportunities
+}
+
+impl ReportOptions {
+    pub(crate) fn matches(&self, trade_opportunity: &DBTradeOpportunity) -> bool {
+        if !self.include_suspicious && trade_opportunity.is_suspicious() {
+            return false;
+        }
+
+        if !self.include_stale && trade_opportunity.is_stale {
+            return false;
+        }
+
+        if let Some(min_profit) = self.min_profit {
+            if trade_opportunity.expected_profit < min_profit {
+                return false;
+            }
+        }
+
+        if let Some(min_velocity) = self.min_velocity {
+            if trade_opportunity.forecast_velocity < min_velocity {
+                return false;
+            }
+        }
+
+        if !self.search_category_ids.is_empty()
+            && !trade_opportunity
+                .search_category_id
+                .is_some_and(|id| self.search_category_ids.contains(&id))
+        {
+            return false;
+        }
+
+        if !self.ui_category_ids.is_empty()
+            && !trade_opportunity
+                .ui_category_id
+                .is_some_and(|id| self.ui_category_ids.contains(&id))
+        {
+            return false;
+        }
+
+        if let Some(cheapest_world_name) = &self.cheapest_world_name {
+            if !trade_opportunity
+                .cheapest_world_name
+                .eq_ignore_ascii_case(cheapest_world_name)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+pub fn print_report(trade_opportunities: &[DBTradeOpportunity]) {
+    println!(
+        "{:<40} {:<14} {:>10} {:>10} {:>10} {:>8} {:>12} {:>6} {:>6}",
+        "Item", "Buy on", "Buy", "Sell", "Profit", "Per day", "Gil per day", "Supply", "Conf"
+    );
+
+    for trade_opportunity in trade_opportunities {
+        println!(
+            "{:<40} {:<14} {:>10.0} {:>10.0} {:>10.0} {:>8.2} {:>12.0} {:>6.1} {:>6.2}",
+            trade_opportunity.item_name,
+            trade_opportunity.cheapest_world_name,
+            trade_opportunity.unit_cost(),
+            trade_opportunity.home_world_avg_price,
+            trade_opportunity.unit_profit,
+            trade_opportunity.forecast_velocity,
+            trade_opportunity.expected_profit,
+            trade_opportunity.days_of_supply,
+            trade_opportunity.confidence_score
+        );
+    }
+}
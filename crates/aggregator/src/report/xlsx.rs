@@ -0,0 +1,208 @@
+use crate::db::DBTradeOpportunity;
+use crate::report::errors::Error;
+use rust_xlsxwriter::{ColNum, Format, Workbook, Worksheet};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Excel doesn't allow longer sheet names
+const SHEET_NAME_LIMIT: usize = 31;
+
+pub struct WorkbookSheet {
+    pub name: String,
+    pub trade_opportunities: Vec<DBTradeOpportunity>,
+}
+
+enum Cell {
+    Text(String),
+    Gil(f64),
+    Integer(u64),
+    Number(f64),
+}
+
+struct Column {
+    header: &'static str,
+    width: f64,
+    value: fn(&DBTradeOpportunity) -> Cell,
+}
+
+const COLUMNS: [Column; 17] = [
+    Column {
+        header: "Item",
+        width: 36.,
+        value: |row| Cell::Text(row.item_name.clone()),
+    },
+    Column {
+        header: "Item ID",
+        width: 9.,
+        value: |row| Cell::Integer(row.item_id),
+    },
+    Column {
+        header: "Buy on",
+        width: 14.,
+        value: |row| Cell::Text(row.cheapest_world_name.clone()),
+    },
+    Column {
+        header: "Buy price",
+        width: 12.,
+        value: |row| Cell::Gil(row.unit_cost()),
+    },
+    Column {
+        header: "Cheapest world price",
+        width: 12.,
+        value: |row| Cell::Gil(row.cheapest_world_avg_price),
+    },
+    Column {
+        header: "Home world price",
+        width: 12.,
+        value: |row| Cell::Gil(row.home_world_avg_price),
+    },
+    Column {
+        header: "Unit profit",
+        width: 12.,
+        value: |row| Cell::Gil(row.unit_profit),
+    },
+    Column {
+        header: "Sales per day",
+        width: 10.,
+        value: |row| Cell::Number(row.forecast_velocity),
+    },
+    Column {
+        header: "Gil per day",
+        width: 14.,
+        value: |row| Cell::Gil(row.expected_profit),
+    },
+    Column {
+        header: "Price diff",
+        width: 10.,
+        value: |row| Cell::Number(row.price_diff_score),
+    },
+    Column {
+        header: "Competing retainers",
+        width: 10.,
+        value: |row| Cell::Integer(row.competing_retainers),
+    },
+    Column {
+        header: "Days of supply",
+        width: 10.,
+        value: |row| Cell::Number(row.days_of_supply),
+    },
+    Column {
+        header: "Saturation",
+        width: 10.,
+        value: |row| Cell::Number(row.saturation_score),
+    },
+    Column {
+        header: "Price CV",
+        width: 10.,
+        value: |row| Cell::Number(row.price_cv),
+    },
+    Column {
+        header: "Confidence",
+        width: 10.,
+        value: |row| Cell::Number(row.confidence_score),
+    },
+    Column {
+        header: "Manipulation flags",
+        width: 20.,
+        value: |row| Cell::Text(row.manipulation_flags.clone()),
+    },
+    Column {
+        header: "Stale",
+        width: 8.,
+        value: |row| Cell::Text(if row.is_stale { "yes" } else { "" }.to_string()),
+    },
+];
+
+/// Writes one sheet per home world with gil formatted columns, a frozen header and auto-filters
+pub fn write_workbook(sheets: &[WorkbookSheet], path: &Path) -> Result<(), Error> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let gil_format = Format::new().set_num_format("#,##0");
+    let number_format = Format::new().set_num_format("0.00");
+
+    for (sheet, name) in sheets.iter().zip(sheet_names(sheets)) {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(name)?;
+
+        write_sheet(
+            worksheet,
+            &sheet.trade_opportunities,
+            &header_format,
+            &gil_format,
+            &number_format,
+        )?;
+    }
+
+    workbook.save(path)?;
+
+    Ok(())
+}
+
+fn write_sheet(
+    worksheet: &mut Worksheet,
+    trade_opportunities: &[DBTradeOpportunity],
+    header_format: &Format,
+    gil_format: &Format,
+    number_format: &Format,
+) -> Result<(), Error> {
+    for (col, column) in COLUMNS.iter().enumerate() {
+        let col = col as ColNum;
+
+        worksheet.write_string_with_format(0, col, column.header, header_format)?;
+        worksheet.set_column_width(col, column.width)?;
+
+        for (row, trade_opportunity) in trade_opportunities.iter().enumerate() {
+            let row = row as u32 + 1;
+
+            match (column.value)(trade_opportunity) {
+                Cell::Text(text) => worksheet.write_string(row, col, text)?,
+                Cell::Gil(gil) => worksheet.write_number_with_format(row, col, gil, gil_format)?,
+                Cell::Integer(integer) => worksheet.write_number(row, col, integer as f64)?,
+                Cell::Number(number) => {
+                    worksheet.write_number_with_format(row, col, number, number_format)?
+                }
+            };
+        }
+    }
+
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofilter(
+        0,
+        0,
+        trade_opportunities.len() as u32,
+        COLUMNS.len() as ColNum - 1,
+    )?;
+
+    Ok(())
+}
+
+/// Sheet names Excel accepts, numbered where truncating made them equal
+fn sheet_names(sheets: &[WorkbookSheet]) -> Vec<String> {
+    let mut used_names = HashSet::new();
+
+    sheets
+        .iter()
+        .map(|sheet| {
+            let mut name = sheet_name(&sheet.name, "");
+            let mut number = 1;
+
+            // Excel compares sheet names case-insensitively
+            while !used_names.insert(name.to_lowercase()) {
+                number += 1;
+                name = sheet_name(&sheet.name, &format!(" ({number})"));
+            }
+
+            name
+        })
+        .collect()
+}
+
+fn sheet_name(name: &str, suffix: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|char| !matches!(char, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(SHEET_NAME_LIMIT - suffix.chars().count())
+        .collect();
+
+    format!("{name}{suffix}")
+}
@@ -0,0 +1,12 @@
+use thiserror::Error;
+use tokio::task::JoinError;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Error while executing sql")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("Error while drawing the terminal")]
+    Io(#[from] std::io::Error),
+    #[error("Error while reading terminal events")]
+    TokioJoin(#[from] JoinError),
+}
@@ -0,0 +1,296 @@
+mod errors;
+mod ui;
+
+pub(crate) use errors::*;
+
+use crate::db::{DBTradeOpportunity, WatchlistData};
+use crate::report::ReportSort;
+use clap::ValueEnum;
+use crossterm::cursor::Show;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::widgets::TableState;
+use ratatui::Terminal;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{self, Stdout, Write};
+use std::path::PathBuf;
+
+const PAGE_SIZE: isize = 10;
+
+pub struct TuiData {
+    pub home_world_name: String,
+    pub trade_opportunities: Vec<DBTradeOpportunity>,
+    /// Average prices of the latest sync run per item by world name
+    pub world_prices: HashMap<u64, Vec<(String, f64)>>,
+    /// Home world average prices per item, the oldest first
+    pub price_history: HashMap<u64, Vec<f64>>,
+}
+
+pub struct TuiOptions {
+    pub sort: ReportSort,
+    pub ascending: bool,
+    pub watchlist: String,
+    /// File the `item_id:quantity` lines for the route command are appended to
+    pub shopping_list: PathBuf,
+    /// Days of home world sales a shopping list entry buys
+    pub days: f64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum InputMode {
+    Normal,
+    Filter,
+}
+
+enum Action {
+    Quit,
+    AddToWatchlist(u64),
+    AddToShoppingList(u64, u64),
+}
+
+struct App {
+    data: TuiData,
+    sort: ReportSort,
+    ascending: bool,
+    filter: String,
+    input_mode: InputMode,
+    /// Indexes of the trade opportunities matching the filter, in display order
+    rows: Vec<usize>,
+    table_state: TableState,
+    status: String,
+}
+
+impl App {
+    fn new(data: TuiData, options: &TuiOptions) -> Self {
+        let mut app = App {
+            data,
+            sort: options.sort,
+            ascending: options.ascending,
+            filter: String::new(),
+            input_mode: InputMode::Normal,
+            rows: Vec::new(),
+            table_state: TableState::default(),
+            status: String::new(),
+        };
+        app.refresh_rows();
+
+        app
+    }
+
+    fn selected(&self) -> Option<&DBTradeOpportunity> {
+        let row = *self.rows.get(self.table_state.selected()?)?;
+
+        self.data.trade_opportunities.get(row)
+    }
+
+    /// Reapplies the filter and sort, keeping the selected item when it is still shown
+    fn refresh_rows(&mut self) {
+        let selected_item_id = self
+            .selected()
+            .map(|trade_opportunity| trade_opportunity.item_id);
+        let filter = self.filter.to_lowercase();
+        let trade_opportunities = &self.data.trade_opportunities;
+
+        self.rows = (0..trade_opportunities.len())
+            .filter(|&index| {
+                let trade_opportunity = &trade_opportunities[index];
+
+                trade_opportunity.item_name.to_lowercase().contains(&filter)
+                    || trade_opportunity
+                        .cheapest_world_name
+                        .to_lowercase()
+                        .contains(&filter)
+            })
+            .collect();
+
+        self.rows.sort_by(|&a, &b| {
+            let ordering = self
+                .sort
+                .value(&trade_opportunities[a])
+                .total_cmp(&self.sort.value(&trade_opportunities[b]));
+
+            if self.ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+
+        let selected = selected_item_id
+            .and_then(|item_id| {
+                self.rows
+                    .iter()
+                    .position(|&index| trade_opportunities[index].item_id == item_id)
+            })
+            .or((!self.rows.is_empty()).then_some(0));
+
+        self.table_state.select(selected);
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+
+        let selected = self.table_state.selected().unwrap_or_default() as isize + delta;
+        let selected = selected.clamp(0, self.rows.len() as isize - 1);
+
+        self.table_state.select(Some(selected as usize));
+    }
+
+    fn next_sort(&mut self) {
+        let sorts = ReportSort::value_variants();
+        let index = sorts.iter().position(|sort| *sort == self.sort);
+
+        self.sort = sorts[index.map_or(0, |index| (index + 1) % sorts.len())];
+        self.refresh_rows();
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, days: f64) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Action::Quit);
+        }
+
+        if self.input_mode == InputMode::Filter {
+            match key.code {
+                KeyCode::Enter => self.input_mode = InputMode::Normal,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.input_mode = InputMode::Normal;
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(char) => self.filter.push(char),
+                _ => return None,
+            }
+
+            self.refresh_rows();
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(PAGE_SIZE),
+            KeyCode::PageUp => self.move_selection(-PAGE_SIZE),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN / 2),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX / 2),
+            KeyCode::Char('/') => self.input_mode = InputMode::Filter,
+            KeyCode::Char('s') => self.next_sort(),
+            KeyCode::Char('r') => {
+                self.ascending = !self.ascending;
+                self.refresh_rows();
+            }
+            KeyCode::Char('w') => {
+                return self
+                    .selected()
+                    .map(|trade_opportunity| Action::AddToWatchlist(trade_opportunity.item_id));
+            }
+            KeyCode::Char('b') => {
+                return self.selected().map(|trade_opportunity| {
+                    let quantity = (trade_opportunity.forecast_velocity * days).floor() as u64;
+
+                    Action::AddToShoppingList(trade_opportunity.item_id, quantity.max(1))
+                });
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn item_name(&self, item_id: u64) -> &str {
+        self.data
+            .trade_opportunities
+            .iter()
+            .find(|trade_opportunity| trade_opportunity.item_id == item_id)
+            .map(|trade_opportunity| trade_opportunity.item_name.as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// Restores the terminal when dropped, also when the app returns early or panics
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+    }
+}
+
+/// Interactive table of trade opportunities, restores the terminal before returning
+pub async fn run_tui(
+    data: TuiData,
+    options: &TuiOptions,
+    watchlist_data: &WatchlistData,
+) -> Result<(), Error> {
+    enable_raw_mode()?;
+    let _terminal_guard = TerminalGuard;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let mut app = App::new(data, options);
+
+    run_app(&mut terminal, &mut app, options, watchlist_data).await
+}
+
+async fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+    options: &TuiOptions,
+    watchlist_data: &WatchlistData,
+) -> Result<(), Error> {
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        // Reading blocks until the next event, so it must not hold up a runtime worker
+        let Event::Key(key) = tokio::task::spawn_blocking(event::read).await?? else {
+            continue;
+        };
+
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        app.status.clear();
+
+        match app.handle_key(key, options.days) {
+            Some(Action::Quit) => return Ok(()),
+            Some(Action::AddToWatchlist(item_id)) => {
+                watchlist_data
+                    .add_items(options.watchlist.clone(), vec![item_id])
+                    .await?;
+
+                app.status = format!(
+                    "Added {} to watchlist {}",
+                    app.item_name(item_id),
+                    options.watchlist
+                );
+            }
+            Some(Action::AddToShoppingList(item_id, quantity)) => {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&options.shopping_list)?;
+                writeln!(file, "{item_id}:{quantity}")?;
+
+                app.status = format!(
+                    "Added {} x{} to {}",
+                    app.item_name(item_id),
+                    quantity,
+                    options.shopping_list.display()
+                );
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use super::{App, InputMode};
+use crate::db::DBTradeOpportunity;
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{
+    Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table,
+};
+use ratatui::Frame;
+
+const TABLE_WIDTHS: [Constraint; 7] = [
+    Constraint::Min(24),
+    Constraint::Length(14),
+    Constraint::Length(10),
+    Constraint::Length(10),
+    Constraint::Length(10),
+    Constraint::Length(8),
+    Constraint::Length(12),
+];
+
+const HELP: &str = "q quit  / filter  s sort  r reverse  w watchlist  b shopping list";
+
+pub(super) fn draw<B: Backend>(frame: &mut Frame<B>, app: &mut App) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(layout[0]);
+    let detail = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(columns[1]);
+
+    draw_table(frame, app, columns[0]);
+
+    if let Some(trade_opportunity) = app.selected() {
+        draw_detail(frame, app, trade_opportunity, detail[0]);
+        draw_price_history(frame, app, trade_opportunity, detail[1]);
+    }
+
+    let status_line = match app.input_mode {
+        InputMode::Filter => format!("Filter: {}_", app.filter),
+        InputMode::Normal if !app.status.is_empty() => app.status.clone(),
+        InputMode::Normal => HELP.to_string(),
+    };
+    frame.render_widget(Paragraph::new(status_line), layout[1]);
+}
+
+fn draw_table<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let header = Row::new(
+        [
+            "Item",
+            "Buy on",
+            "Buy",
+            "Sell",
+            "Profit",
+            "Per day",
+            "Gil per day",
+        ]
+        .map(|header| Cell::from(header).style(Style::default().add_modifier(Modifier::BOLD))),
+    );
+
+    let rows = app.rows.iter().map(|&index| {
+        let trade_opportunity = &app.data.trade_opportunities[index];
+
+        Row::new(vec![
+            Cell::from(trade_opportunity.item_name.clone()),
+            Cell::from(trade_opportunity.cheapest_world_name.clone()),
+            Cell::from(format!("{:>10.0}", trade_opportunity.unit_cost())),
+            Cell::from(format!("{:>10.0}", trade_opportunity.home_world_avg_price)),
+            Cell::from(format!("{:>10.0}", trade_opportunity.unit_profit)),
+            Cell::from(format!("{:>8.2}", trade_opportunity.forecast_velocity)),
+            Cell::from(format!("{:>12.0}", trade_opportunity.expected_profit)),
+        ])
+    });
+
+    let title = format!(
+        "{} - {} of {} items by {:?} {}",
+        app.data.home_world_name,
+        app.rows.len(),
+        app.data.trade_opportunities.len(),
+        app.sort,
+        if app.ascending { "asc" } else { "desc" }
+    );
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&TABLE_WIDTHS)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(table, area, &mut app.table_state);
+}
+
+fn draw_detail<B: Backend>(
+    frame: &mut Frame<B>,
+    app: &App,
+    trade_opportunity: &DBTradeOpportunity,
+    area: Rect,
+) {
+    let mut lines = vec![
+        Spans::from(format!(
+            "Buy {:.0} on {}, sell {:.0}",
+            trade_opportunity.unit_cost(),
+            trade_opportunity.cheapest_world_name,
+            trade_opportunity.home_world_avg_price
+        )),
+        Spans::from(format!(
+            "{:.2} per day, {:.1} days of supply, {} competing retainers",
+            trade_opportunity.forecast_velocity,
+            trade_opportunity.days_of_supply,
+            trade_opportunity.competing_retainers
+        )),
+        Spans::from(format!(
+            "Confidence {:.2} from {} sales",
+            trade_opportunity.confidence_score, trade_opportunity.sample_count
+        )),
+    ];
+
+    if trade_opportunity.is_suspicious() {
+        lines.push(Spans::from(Span::styled(
+            format!("Flagged: {}", trade_opportunity.manipulation_flags),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    if trade_opportunity.is_stale {
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "Stale data, uploaded {:.1}h ago",
+                trade_opportunity.data_age as f64 / 3600.
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    lines.push(Spans::from(""));
+
+    let mut world_prices = app
+        .data
+        .world_prices
+        .get(&trade_opportunity.item_id)
+        .cloned()
+        .unwrap_or_default();
+    world_prices.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    for (world_name, avg_price) in world_prices {
+        let style = if world_name == trade_opportunity.cheapest_world_name {
+            Style::default().fg(Color::Green)
+        } else if world_name == app.data.home_world_name {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+
+        lines.push(Spans::from(Span::styled(
+            format!("{world_name:<16} {avg_price:>12.0}"),
+            style,
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(trade_opportunity.item_name.clone()),
+    );
+
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_price_history<B: Backend>(
+    frame: &mut Frame<B>,
+    app: &App,
+    trade_opportunity: &DBTradeOpportunity,
+    area: Rect,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} price history", app.data.home_world_name));
+
+    let points: Vec<(f64, f64)> = app
+        .data
+        .price_history
+        .get(&trade_opportunity.item_id)
+        .map(|prices| {
+            prices
+                .iter()
+                .enumerate()
+                .map(|(index, price)| (index as f64, *price))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if points.len() < 2 {
+        frame.render_widget(Paragraph::new("Not enough sync runs").block(block), area);
+        return;
+    }
+
+    let min = points
+        .iter()
+        .map(|(_, price)| *price)
+        .fold(f64::INFINITY, f64::min);
+    let max = points
+        .iter()
+        .map(|(_, price)| *price)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let (min, max) = if max > min {
+        (min, max)
+    } else {
+        (min - 1., max + 1.)
+    };
+
+    let datasets = vec![Dataset::default()
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Color::Cyan))
+        .data(&points)];
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .bounds([0., (points.len() - 1) as f64])
+                .labels(vec![Span::from("oldest"), Span::from("latest")]),
+        )
+        .y_axis(Axis::default().bounds([min, max]).labels(vec![
+            Span::from(format!("{min:.0}")),
+            Span::from(format!("{max:.0}")),
+        ]));
+
+    frame.render_widget(chart, area);
+}
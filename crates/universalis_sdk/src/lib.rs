@@ -3,18 +3,19 @@
 extern crate core;
 
 pub mod errors;
+mod rate_limit;
 pub mod xivapi;
 
 use crate::errors::Error;
-use crate::errors::Error::StringError;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use crate::rate_limit::{wait_for_request_slot, RateLimitMiddleware};
+use reqwest_middleware::ClientBuilder;
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Write;
 use url::Url;
 
+pub use crate::rate_limit::set_max_requests_per_second;
+
 const UNIVERSALIS_URL: &str = "https://universalis.app/api/v2";
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -40,6 +41,26 @@ pub struct ItemSaleHistoryUnit {
     pub quantity: u64,
     #[serde(alias = "pricePerUnit")]
     pub price_per_unit: u64,
+    #[serde(default)]
+    pub hq: bool,
+    #[serde(default)]
+    pub timestamp: u64,
+    #[serde(alias = "buyerName", default)]
+    pub buyer_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ItemListing {
+    #[serde(alias = "pricePerUnit")]
+    pub price_per_unit: u64,
+    pub quantity: u64,
+    pub hq: bool,
+    #[serde(alias = "retainerID", default)]
+    pub retainer_id: String,
+    #[serde(alias = "retainerName", default)]
+    pub retainer_name: String,
+    #[serde(alias = "lastReviewTime", default)]
+    pub last_review_time: u64,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -50,6 +71,77 @@ pub struct ItemTradeVolume {
     pub sale_score: f64,
     pub price_diff_score: f64,
     pub home_world_avg_price: f64,
+    pub cheapest_world_avg_price: f64,
+    /// Gil earned per unit bought on the cheapest world and sold on the home world
+    pub unit_profit: f64,
+    /// Forecast home world units sold per day, `sale_score` is Universalis' snapshot
+    pub forecast_velocity: f64,
+    /// Expected gil per day from reselling on the home world
+    pub expected_profit: f64,
+    pub competing_retainers: u64,
+    pub days_of_supply: f64,
+    pub lowest_listing_changes: u64,
+    pub saturation_score: f64,
+    /// Home world sale price volatility
+    pub price_std_dev: f64,
+    pub price_cv: f64,
+    pub sample_count: u64,
+    pub confidence_score: f64,
+    /// Bitmask of suspected market manipulation patterns
+    pub manipulation_flags: u64,
+    /// Seconds since the older of the home world and cheapest world data was uploaded
+    pub data_age: u64,
+    pub is_stale: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TaxRates {
+    #[serde(alias = "Limsa Lominsa")]
+    pub limsa_lominsa: u64,
+    #[serde(alias = "Gridania")]
+    pub gridania: u64,
+    #[serde(alias = "Ul'dah")]
+    pub uldah: u64,
+    #[serde(alias = "Ishgard")]
+    pub ishgard: u64,
+    #[serde(alias = "Kugane")]
+    pub kugane: u64,
+    #[serde(alias = "Crystarium")]
+    pub crystarium: u64,
+    #[serde(alias = "Old Sharlayan", default)]
+    pub old_sharlayan: Option<u64>,
+    #[serde(alias = "Tuliyollal", default)]
+    pub tuliyollal: Option<u64>,
+}
+
+impl TaxRates {
+    pub fn get_cities_rates(&self) -> Vec<(&'static str, u64)> {
+        let mut cities_rates = vec![
+            ("Limsa Lominsa", self.limsa_lominsa),
+            ("Gridania", self.gridania),
+            ("Ul'dah", self.uldah),
+            ("Ishgard", self.ishgard),
+            ("Kugane", self.kugane),
+            ("Crystarium", self.crystarium),
+        ];
+
+        if let Some(rate) = self.old_sharlayan {
+            cities_rates.push(("Old Sharlayan", rate));
+        }
+
+        if let Some(rate) = self.tuliyollal {
+            cities_rates.push(("Tuliyollal", rate));
+        }
+
+        cities_rates
+    }
+
+    pub fn get_lowest_rate_city(&self) -> (&'static str, u64) {
+        self.get_cities_rates()
+            .into_iter()
+            .min_by_key(|(_, rate)| *rate)
+            .unwrap_or(("Limsa Lominsa", self.limsa_lominsa))
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -79,6 +171,27 @@ pub struct ItemSaleHistory {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ItemMapping {
     pub entries: Vec<ItemSaleHistoryUnit>,
+    /// Unix timestamp in milliseconds
+    #[serde(alias = "lastUploadTime", default)]
+    pub last_upload_time: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ItemListings {
+    pub items: HashMap<u64, ItemListingsMapping>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ItemListingsMapping {
+    pub listings: Vec<ItemListing>,
+    #[serde(alias = "unitsForSale", default)]
+    pub units_for_sale: u64,
+    /// Units sold per day averaged over Universalis' sale history window
+    #[serde(alias = "regularSaleVelocity", default)]
+    pub sale_velocity: f64,
+    /// Unix timestamp in milliseconds
+    #[serde(alias = "lastUploadTime", default)]
+    pub last_upload_time: u64,
 }
 
 pub async fn get_servers() -> Result<Vec<Server>, Error> {
@@ -101,16 +214,12 @@ async fn get_data_center_worlds(data_center: &DataCenter) -> Result<Vec<World>,
         .map_err(|_| Error::UrlParseBase)?
         .push("worlds");
 
+    wait_for_request_slot().await;
     let body_worlds = reqwest::get(url.as_str()).await?.text().await?;
 
     let mut worlds: Vec<World> = serde_json::from_str(&body_worlds)?;
 
-    worlds.retain(|world| {
-        data_center
-            .worlds
-            .iter()
-            .any(|&dc_world_id| dc_world_id == world.id)
-    });
+    worlds.retain(|world| data_center.worlds.contains(&world.id));
 
     Ok(worlds)
 }
@@ -121,6 +230,7 @@ async fn get_data_centers() -> Result<Vec<DataCenter>, Error> {
         .map_err(|_| Error::UrlParseBase)?
         .push("data-centers");
 
+    wait_for_request_slot().await;
     let body_data_centers = reqwest::get(url.as_str()).await?.text().await?;
 
     let data_centers = serde_json::from_str(&body_data_centers)?;
@@ -134,6 +244,7 @@ pub async fn get_marketable_items_ids() -> Result<Vec<u64>, Error> {
         .map_err(|_| Error::UrlParseBase)?
         .push("marketable");
 
+    wait_for_request_slot().await;
     let body_marketable_items = reqwest::get(url.as_str()).await?.text().await?;
 
     let marketable_items_ids = serde_json::from_str(&body_marketable_items)?;
@@ -141,6 +252,21 @@ pub async fn get_marketable_items_ids() -> Result<Vec<u64>, Error> {
     Ok(marketable_items_ids)
 }
 
+pub async fn get_tax_rates_by_world(world_name: String) -> Result<TaxRates, Error> {
+    let mut url = Url::parse(UNIVERSALIS_URL)?;
+    url.path_segments_mut()
+        .map_err(|_| Error::UrlParseBase)?
+        .push("tax-rates");
+    url.query_pairs_mut().append_pair("world", &world_name);
+
+    wait_for_request_slot().await;
+    let body_tax_rates = reqwest::get(url.as_str()).await?.text().await?;
+
+    let tax_rates = serde_json::from_str(&body_tax_rates)?;
+
+    Ok(tax_rates)
+}
+
 pub async fn get_item_velocity_by_world(
     item_id: u64,
     world_name: String,
@@ -155,6 +281,7 @@ pub async fn get_item_velocity_by_world(
     url.query_pairs_mut()
         .append_pair("fields", "nqSaleVelocity,hqSaleVelocity");
 
+    wait_for_request_slot().await;
     let body_velocity = reqwest::get(url.as_str()).await?.text().await?;
 
     let item_velocity = serde_json::from_str(&body_velocity[..])?;
@@ -162,9 +289,12 @@ pub async fn get_item_velocity_by_world(
     Ok(item_velocity)
 }
 
+/// Sale history of the items, `entries_within` limits it to the last given seconds
+/// instead of Universalis' default window
 pub async fn get_item_sale_history_by_world(
     item_ids: Vec<u64>,
     world_name: String,
+    entries_within: Option<u64>,
 ) -> Result<ItemSaleHistory, Error> {
     let mut url = Url::parse(UNIVERSALIS_URL)?;
     url.path_segments_mut()
@@ -184,14 +314,71 @@ pub async fn get_item_sale_history_by_world(
         .map_err(|_| Error::UrlParseBase)?
         .push(&ids_param);
 
+    if let Some(entries_within) = entries_within {
+        url.query_pairs_mut()
+            .append_pair("entriesWithin", &entries_within.to_string());
+    }
+
     let retry_policy = ExponentialBackoff::builder().build_with_max_retries(10);
     let client = ClientBuilder::new(reqwest::Client::new())
         .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .with(RateLimitMiddleware)
         .build();
 
     let body_sale_history = client.get(url).send().await?.text().await?;
 
+    // Universalis returns a bare item object instead of the `items` map for a single id
+    if let [item_id] = item_ids[..] {
+        let item_mapping: ItemMapping = serde_json::from_str(&body_sale_history)?;
+
+        return Ok(ItemSaleHistory {
+            items: HashMap::from([(item_id, item_mapping)]),
+        });
+    }
+
     let sale_history = serde_json::from_str(&body_sale_history)?;
 
     Ok(sale_history)
 }
+
+pub async fn get_item_listings_by_world(
+    item_ids: Vec<u64>,
+    world_name: String,
+) -> Result<ItemListings, Error> {
+    let mut url = Url::parse(UNIVERSALIS_URL)?;
+    url.path_segments_mut()
+        .map_err(|_| Error::UrlParseBase)?
+        .push(&world_name);
+
+    let ids_param = item_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    url.path_segments_mut()
+        .map_err(|_| Error::UrlParseBase)?
+        .push(&ids_param);
+    url.query_pairs_mut().append_pair("entries", "0");
+
+    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(10);
+    let client = ClientBuilder::new(reqwest::Client::new())
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .with(RateLimitMiddleware)
+        .build();
+
+    let body_listings = client.get(url).send().await?.text().await?;
+
+    // Universalis returns a bare item object instead of the `items` map for a single id
+    if let [item_id] = item_ids[..] {
+        let item_listings: ItemListingsMapping = serde_json::from_str(&body_listings)?;
+
+        return Ok(ItemListings {
+            items: HashMap::from([(item_id, item_listings)]),
+        });
+    }
+
+    let listings = serde_json::from_str(&body_listings)?;
+
+    Ok(listings)
+}
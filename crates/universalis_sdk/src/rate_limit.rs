@@ -0,0 +1,63 @@
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use task_local_extensions::Extensions;
+
+/// Minimum time between two Universalis requests in microseconds, 0 disables the limit
+static REQUEST_INTERVAL: AtomicU64 = AtomicU64::new(0);
+static NEXT_REQUEST_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Spaces out Universalis requests of the whole process, 0 or less removes the limit
+pub fn set_max_requests_per_second(max_requests_per_second: f64) {
+    let interval = if max_requests_per_second > 0. {
+        (1_000_000. / max_requests_per_second) as u64
+    } else {
+        0
+    };
+
+    REQUEST_INTERVAL.store(interval, Ordering::Relaxed);
+}
+
+/// Waits until the next request fits in the rate limit and reserves its slot
+pub(crate) async fn wait_for_request_slot() {
+    let interval = Duration::from_micros(REQUEST_INTERVAL.load(Ordering::Relaxed));
+
+    if interval.is_zero() {
+        return;
+    }
+
+    let wait = {
+        let mut next_request_at = NEXT_REQUEST_AT
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let now = Instant::now();
+        let request_at = next_request_at.map_or(now, |next_request_at| next_request_at.max(now));
+
+        *next_request_at = Some(request_at + interval);
+
+        request_at - now
+    };
+
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Takes a rate limit slot for every attempt. Added after the retry middleware so retries are
+/// limited too
+pub(crate) struct RateLimitMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        wait_for_request_slot().await;
+        next.run(req, extensions).await
+    }
+}
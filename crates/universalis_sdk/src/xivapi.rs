@@ -10,6 +10,18 @@ pub struct Item {
     pub id: u64,
     #[serde(alias = "Name")]
     pub name: String,
+    #[serde(alias = "ItemSearchCategoryTargetID", default)]
+    pub search_category_id: Option<u64>,
+    #[serde(alias = "ItemUICategoryTargetID", default)]
+    pub ui_category_id: Option<u64>,
+    #[serde(alias = "LevelItem", default)]
+    pub level_item: u64,
+    #[serde(alias = "StackSize", default = "default_stack_size")]
+    pub stack_size: u64,
+}
+
+fn default_stack_size() -> u64 {
+    1
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -31,7 +43,12 @@ pub async fn get_all_items() -> Result<Vec<Item>, Error> {
     url.path_segments_mut()
         .map_err(|_| Error::UrlParseBase)?
         .push("search");
-    url.query_pairs_mut().append_pair("indexes", "Item");
+    url.query_pairs_mut()
+        .append_pair("indexes", "Item")
+        .append_pair(
+            "columns",
+            "ID,Name,ItemSearchCategoryTargetID,ItemUICategoryTargetID,LevelItem,StackSize",
+        );
     let mut page = 1;
     let mut results = vec![];
 
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS sync_runs
+(
+    `id`             BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    `data_center_id` BIGINT UNSIGNED NOT NULL,
+    `world_id`       BIGINT UNSIGNED NOT NULL,
+    `status`         VARCHAR(16)     NOT NULL DEFAULT 'running',
+    `items_count`    BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    `started_at`     DATETIME        NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    `finished_at`    DATETIME        NULL,
+    PRIMARY KEY (id),
+    FOREIGN KEY (data_center_id) REFERENCES data_centers (id),
+    FOREIGN KEY (world_id) REFERENCES worlds (world_id)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE items_trade_volumes
+ADD COLUMN `sync_run_id` BIGINT UNSIGNED NULL,
+ADD FOREIGN KEY (sync_run_id) REFERENCES sync_runs (id);
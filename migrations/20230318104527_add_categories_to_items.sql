@@ -0,0 +1,4 @@
+ALTER TABLE items
+ADD COLUMN `search_category_id` BIGINT UNSIGNED NULL,
+ADD COLUMN `ui_category_id`     BIGINT UNSIGNED NULL,
+ADD COLUMN `level_item`         BIGINT UNSIGNED NOT NULL DEFAULT 0;
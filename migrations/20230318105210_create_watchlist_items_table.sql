@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS watchlist_items
+(
+    `id`      BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    `name`    VARCHAR(64)     NOT NULL,
+    `item_id` BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE (name, item_id),
+    FOREIGN KEY (item_id) REFERENCES items (item_id)
+);
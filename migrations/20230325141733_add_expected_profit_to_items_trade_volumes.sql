@@ -0,0 +1,3 @@
+ALTER TABLE items_trade_volumes
+ADD COLUMN `cheapest_world_avg_price` DOUBLE NOT NULL DEFAULT 0,
+ADD COLUMN `expected_profit`          DOUBLE NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE sync_runs
+ADD COLUMN `rejected_items_count` BIGINT UNSIGNED NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS tax_rates
+(
+    `id`          BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    `sync_run_id` BIGINT UNSIGNED NOT NULL,
+    `world_id`    BIGINT UNSIGNED NOT NULL,
+    `city`        VARCHAR(32)     NOT NULL,
+    `rate`        BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (id),
+    FOREIGN KEY (sync_run_id) REFERENCES sync_runs (id),
+    FOREIGN KEY (world_id) REFERENCES worlds (world_id)
+);
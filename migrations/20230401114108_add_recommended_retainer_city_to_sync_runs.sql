@@ -0,0 +1,2 @@
+ALTER TABLE sync_runs
+ADD COLUMN `recommended_retainer_city` VARCHAR(32) NULL;
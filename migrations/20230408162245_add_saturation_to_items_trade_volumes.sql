@@ -0,0 +1,5 @@
+ALTER TABLE items_trade_volumes
+ADD COLUMN `competing_retainers`    BIGINT UNSIGNED NOT NULL DEFAULT 0,
+ADD COLUMN `days_of_supply`         DOUBLE          NOT NULL DEFAULT 0,
+ADD COLUMN `lowest_listing_changes` BIGINT UNSIGNED NOT NULL DEFAULT 0,
+ADD COLUMN `saturation_score`       DOUBLE          NOT NULL DEFAULT 0;
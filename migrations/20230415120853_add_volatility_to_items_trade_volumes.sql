@@ -0,0 +1,5 @@
+ALTER TABLE items_trade_volumes
+ADD COLUMN `price_std_dev`    DOUBLE          NOT NULL DEFAULT 0,
+ADD COLUMN `price_cv`         DOUBLE          NOT NULL DEFAULT 0,
+ADD COLUMN `sample_count`     BIGINT UNSIGNED NOT NULL DEFAULT 0,
+ADD COLUMN `confidence_score` DOUBLE          NOT NULL DEFAULT 0;
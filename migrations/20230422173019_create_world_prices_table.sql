@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS world_prices
+(
+    `id`           BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+    `sync_run_id`  BIGINT UNSIGNED NOT NULL,
+    `item_id`      BIGINT UNSIGNED NOT NULL,
+    `world_id`     BIGINT UNSIGNED NOT NULL,
+    `avg_price`    DOUBLE          NOT NULL,
+    `sample_count` BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (id),
+    INDEX (world_id, item_id),
+    FOREIGN KEY (sync_run_id) REFERENCES sync_runs (id),
+    FOREIGN KEY (item_id) REFERENCES items (item_id),
+    FOREIGN KEY (world_id) REFERENCES worlds (world_id)
+);
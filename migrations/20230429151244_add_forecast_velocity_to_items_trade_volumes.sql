@@ -0,0 +1,3 @@
+ALTER TABLE items_trade_volumes
+ADD COLUMN `unit_profit`       DOUBLE NOT NULL DEFAULT 0,
+ADD COLUMN `forecast_velocity` DOUBLE NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE items_trade_volumes
+ADD COLUMN `manipulation_flags` SET ('wash_trading', 'dominant_retainer', 'artificial_floor') NOT NULL DEFAULT '';
@@ -0,0 +1,3 @@
+ALTER TABLE items_trade_volumes
+ADD COLUMN `data_age` BIGINT UNSIGNED NOT NULL DEFAULT 0,
+ADD COLUMN `is_stale` BOOLEAN         NOT NULL DEFAULT FALSE;
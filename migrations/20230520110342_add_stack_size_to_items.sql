@@ -0,0 +1,2 @@
+ALTER TABLE items
+ADD COLUMN `stack_size` BIGINT UNSIGNED NOT NULL DEFAULT 1;
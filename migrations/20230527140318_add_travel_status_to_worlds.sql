@@ -0,0 +1,2 @@
+ALTER TABLE worlds
+ADD COLUMN `travel_status` VARCHAR(16) NOT NULL DEFAULT 'open';